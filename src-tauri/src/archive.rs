@@ -0,0 +1,83 @@
+use crate::settings::get_settings_path;
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+// Archive lines use yt-dlp's "<extractor> <id>" format, so the file also
+// works with `yt-dlp --download-archive`
+const EXTRACTOR: &str = "youtube";
+
+#[derive(Default)]
+struct ArchiveInner {
+    path: Option<PathBuf>,
+    ids: HashSet<String>,
+}
+
+// Video ids of completed downloads, kept in download-archive.txt next to settings.json
+#[derive(Default)]
+pub struct DownloadArchive {
+    inner: Mutex<ArchiveInner>,
+}
+
+impl DownloadArchive {
+    pub fn load(&self, app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let settings_path = get_settings_path(app_handle)?;
+        let path = settings_path
+            .parent()
+            .ok_or("Failed to resolve config directory")?
+            .join("download-archive.txt");
+
+        let ids = if path.exists() {
+            fs::read_to_string(&path)?
+                .lines()
+                .filter_map(|line| line.split_whitespace().nth(1))
+                .map(|id| id.to_string())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        inner.path = Some(path);
+        inner.ids = ids;
+
+        Ok(())
+    }
+
+    pub fn contains(&self, video_id: &str) -> bool {
+        self.inner
+            .lock()
+            .map(|inner| inner.ids.contains(video_id))
+            .unwrap_or(false)
+    }
+
+    pub fn add(&self, video_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        if !inner.ids.insert(video_id.to_string()) {
+            return Ok(());
+        }
+
+        if let Some(path) = &inner.path {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            writeln!(file, "{} {}", EXTRACTOR, video_id)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        inner.ids.clear();
+
+        if let Some(path) = &inner.path {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,16 +1,25 @@
 use crate::downloader::{
-    download_video, set_stop_signal, DownloadItem, DownloadProgress, DownloadStatus,
+    download_video, emit_progress, DownloadError, DownloadItem, DownloadOptions, DownloadProgress,
+    DownloadStatus, TimeRange,
 };
-use crate::playlist::{fetch_playlist_items, Item};
+use crate::error::{ErrorCode, ErrorInfo};
+use crate::formats::DownloadMode;
+use crate::history::{HistoryEntry, NewHistoryEntry};
+use crate::playlist::{
+    fetch_playlist_items, Item, ItemRange, PlaylistError, PlaylistInfo, PlaylistKind,
+};
+use crate::queue::QueuedJob;
 use crate::settings::{
     load_settings_with_handle, save_settings_with_handle, validate_folder_path,
-    validate_parallel_downloads, Settings,
+    validate_parallel_downloads, validate_quality_presets, validate_retry_policy, Settings,
 };
+use crate::tagging::TagPlan;
+use crate::template::{render_preview, to_ytdlp_template, validate_templates, TemplateContext};
 use crate::AppState;
 use rfd::FileDialog;
 use std::process::Command;
 use std::sync::Arc;
-use tauri::{command, AppHandle, Emitter, State};
+use tauri::{command, AppHandle, Emitter, Manager, State};
 use tokio::sync::Semaphore;
 
 // Get current settings
@@ -18,7 +27,7 @@ use tokio::sync::Semaphore;
 pub async fn get_settings(
     app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<Settings, String> {
+) -> Result<Settings, ErrorInfo> {
     match load_settings_with_handle(&app_handle) {
         Ok(settings) => {
             if let Ok(mut state_settings) = state.settings.lock() {
@@ -28,11 +37,10 @@ pub async fn get_settings(
         }
         Err(e) => {
             eprintln!("Failed to load settings: {}", e);
-            state
-                .settings
-                .lock()
-                .map(|s| s.clone())
-                .map_err(|e| format!("Failed to access settings state: {}", e))
+            state.settings.lock().map(|s| s.clone()).map_err(|e| {
+                ErrorCode::StorageError
+                    .with_detail(format!("Failed to access settings state: {}", e))
+            })
         }
     }
 }
@@ -43,39 +51,82 @@ pub async fn save_settings(
     app_handle: AppHandle,
     state: State<'_, AppState>,
     settings: Settings,
-) -> Result<(), String> {
-    validate_folder_path(&settings.download_folder)
-        .map_err(|e| format!("Invalid download folder: {}", e))?;
+) -> Result<(), ErrorInfo> {
+    validate_folder_path(&settings.download_folder).map_err(|e| {
+        ErrorCode::InvalidPath.with_detail(format!("Invalid download folder: {}", e))
+    })?;
 
     validate_parallel_downloads(settings.parallel_downloads)
-        .map_err(|e| format!("Invalid parallel downloads setting: {}", e))?;
+        .map_err(invalid_settings("parallel downloads setting"))?;
 
-    let mut state_settings = state
-        .settings
-        .lock()
-        .map_err(|e| format!("Failed to lock settings state: {}", e))?;
+    validate_quality_presets(&settings).map_err(invalid_settings("quality presets"))?;
+
+    settings
+        .video
+        .validate()
+        .map_err(invalid_settings("video settings"))?;
+
+    validate_retry_policy(&settings.retry).map_err(invalid_settings("retry settings"))?;
+
+    validate_templates(&settings.folder_template, &settings.filename_template)
+        .map_err(invalid_settings("naming template"))?;
+
+    settings
+        .sponsorblock
+        .validate()
+        .map_err(invalid_settings("SponsorBlock settings"))?;
+
+    settings
+        .loudness
+        .validate()
+        .map_err(invalid_settings("loudness settings"))?;
+
+    settings
+        .cover_art
+        .validate()
+        .map_err(invalid_settings("cover art settings"))?;
+
+    settings
+        .lyrics
+        .validate()
+        .map_err(invalid_settings("lyrics settings"))?;
+
+    settings
+        .playlist
+        .validate()
+        .map_err(invalid_settings("playlist settings"))?;
+
+    let mut state_settings = state.settings.lock().map_err(|e| {
+        ErrorCode::StorageError.with_detail(format!("Failed to lock settings state: {}", e))
+    })?;
     *state_settings = settings.clone();
 
-    save_settings_with_handle(&app_handle, &settings)
-        .map_err(|e| format!("Failed to save settings: {}", e))?;
+    save_settings_with_handle(&app_handle, &settings).map_err(|e| {
+        ErrorCode::StorageError.with_detail(format!("Failed to save settings: {}", e))
+    })?;
 
     Ok(())
 }
 
+// Build the error for a failed settings validator, naming the section
+fn invalid_settings(section: &'static str) -> impl Fn(String) -> ErrorInfo {
+    move |e| ErrorCode::InvalidSettings.with_detail(format!("Invalid {}: {}", section, e))
+}
+
 // Set download folder specifically
 #[command]
 pub async fn set_download_folder(
     app_handle: AppHandle,
     state: State<'_, AppState>,
     path: String,
-) -> Result<(), String> {
-    validate_folder_path(&path)?;
+) -> Result<(), ErrorInfo> {
+    validate_folder_path(&path).map_err(|e| ErrorCode::InvalidPath.with_detail(e))?;
 
     let current_settings = get_settings(app_handle.clone(), state.clone()).await?;
 
     let new_settings = Settings {
         download_folder: path,
-        parallel_downloads: current_settings.parallel_downloads,
+        ..current_settings
     };
 
     save_settings(app_handle, state, new_settings).await
@@ -83,83 +134,189 @@ pub async fn set_download_folder(
 
 // Open folder selection dialog
 #[command]
-pub async fn select_folder() -> Result<Option<String>, String> {
+pub async fn select_folder() -> Result<Option<String>, ErrorInfo> {
     let file_path = FileDialog::new().pick_folder();
     Ok(file_path.map(|p| p.display().to_string()))
 }
 
 // fetch playlist from url
 #[command]
-pub async fn fetch_playlist(app: AppHandle, url: String) -> Result<Vec<Item>, String> {
-    fetch_playlist_items(&app,&url).await.map_err(|e| e.to_string())
+pub async fn fetch_playlist(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request_id: String,
+    url: String,
+    start: Option<u32>,
+    end: Option<u32>,
+    limit: Option<u32>,
+) -> Result<PlaylistInfo, PlaylistError> {
+    let options = state
+        .settings
+        .lock()
+        .map(|settings| settings.playlist)
+        .unwrap_or_default();
+    let range = ItemRange { start, end, limit };
+
+    let control = state.fetches.register(&request_id);
+    let result = fetch_playlist_items(&app, &request_id, &url, &range, &options, &control).await;
+    state.fetches.unregister(&request_id, &control);
+
+    result
+}
+
+// stop a running playlist fetch
+#[command]
+pub async fn cancel_fetch(state: State<'_, AppState>, request_id: String) -> Result<(), ErrorInfo> {
+    if state.fetches.cancel(&request_id) {
+        Ok(())
+    } else {
+        Err(ErrorCode::NotFound.with_detail(format!("No playlist fetch with id {}", request_id)))
+    }
+}
+
+// show where an item would be saved with the current naming templates
+#[command]
+pub async fn preview_output_path(
+    item: Item,
+    settings: Settings,
+    playlist_title: Option<String>,
+    playlist_index: Option<u32>,
+) -> Result<String, ErrorInfo> {
+    let context = TemplateContext::from_item(&item, playlist_title, playlist_index);
+    let extension = match settings.mode {
+        DownloadMode::Audio => settings.audio_format.extension(),
+        DownloadMode::Video => settings.video.format.extension(),
+    };
+
+    render_preview(
+        &settings.folder_template,
+        &settings.filename_template,
+        &context,
+        extension,
+    )
+    .map_err(|e| ErrorCode::InvalidSettings.with_detail(e))
 }
 
 // start download
 #[command]
 pub async fn start_downloads(
     app: tauri::AppHandle,
+    state: State<'_, AppState>,
     items: Vec<DownloadItem>,
     settings: Settings,
-) -> Result<(), String> {
-    // Reset stop signal
-    set_stop_signal(false);
+) -> Result<(), ErrorInfo> {
+    // Resolve options up front so a bad preset fails before anything starts
+    let jobs = items
+        .into_iter()
+        .map(|item| {
+            resolve_download_options(&item, &settings)
+                .map(|options| QueuedJob::new(item, options, settings.download_folder.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // In archive mode, videos downloaded by an earlier run are skipped
+    let (skipped, jobs): (Vec<_>, Vec<_>) = jobs
+        .into_iter()
+        .partition(|job| settings.use_download_archive && state.archive.contains(&job.item.id));
+
+    for job in &skipped {
+        emit_progress(
+            &app,
+            DownloadProgress::new(job.item.job_id(), DownloadStatus::Skipped, 100.0),
+        );
+    }
 
-    let semaphore = Arc::new(Semaphore::new(settings.parallel_downloads as usize));
-    let download_folder = Arc::new(settings.download_folder);
+    // Persist the jobs so they can be resumed after a restart
+    for job in &jobs {
+        state.queue.enqueue(job.clone());
+    }
+
+    run_downloads(&app, &state, jobs, settings.parallel_downloads).await;
+
+    Ok(())
+}
+
+// Run queued jobs with limited parallelism and wait for all of them
+async fn run_downloads(app: &AppHandle, state: &AppState, jobs: Vec<QueuedJob>, parallel: u8) {
+    let semaphore = Arc::new(Semaphore::new(parallel as usize));
 
     let mut tasks = Vec::new();
 
-    for item in items {
+    for job in jobs {
+        // Each download gets its own control, so stale cancellations never leak in
+        let control = state.downloads.register(job.item.job_id());
         let app = app.clone();
         let semaphore = semaphore.clone();
-        let download_folder = download_folder.clone();
 
         let task = tokio::spawn(async move {
-            let _permit = semaphore.acquire().await.unwrap();
+            let id = job.item.job_id().to_string();
 
             // Emit pending status
-            let _ = app.emit(
-                "download-progress",
-                &DownloadProgress {
-                    id: item.id.clone(),
-                    status: DownloadStatus::Pending,
-                    progress: 0.0,
-                    speed: None,
-                    eta: None,
-                    downloaded: None,
-                    total_size: None,
-                    error: None,
-                },
+            emit_progress(
+                &app,
+                DownloadProgress::new(&id, DownloadStatus::Pending, 0.0),
             );
 
-            // Start download
-            if let Err(e) = download_video(
+            // Start download; it takes a slot from the semaphore once it runs
+            let result = download_video(
                 app.clone(),
-                item.id.clone(),
-                item.url,
-                download_folder.to_string(),
+                &job.item,
+                job.download_folder,
+                job.options.clone(),
+                control.clone(),
+                semaphore,
             )
-            .await
-            {
-                let status = match e.to_string().as_str() {
-                    "Download cancelled" => DownloadStatus::Cancelled,
+            .await;
+
+            if let Err(e) = &result {
+                let status = match e {
+                    DownloadError::Cancelled => DownloadStatus::Cancelled,
                     _ => DownloadStatus::Error,
                 };
 
-                let _ = app.emit(
-                    "download-progress",
-                    &DownloadProgress {
-                        id: item.id,
-                        status,
-                        progress: 0.0,
-                        speed: None,
-                        eta: None,
-                        downloaded: None,
-                        total_size: None,
+                emit_progress(
+                    &app,
+                    DownloadProgress {
                         error: Some(e.to_string()),
+                        error_info: Some(e.info()),
+                        ..DownloadProgress::new(&id, status, 0.0)
                     },
                 );
             }
+
+            // Downloads are only recorded in the archive while archive mode is on
+            let archive_mode = app
+                .state::<AppState>()
+                .settings
+                .lock()
+                .is_ok_and(|settings| settings.use_download_archive);
+            if result.is_ok() && archive_mode {
+                if let Err(e) = app.state::<AppState>().archive.add(&job.item.id) {
+                    eprintln!("Failed to update download archive: {}", e);
+                }
+            }
+
+            // Cancelled jobs never finished, so they stay out of the history
+            if !matches!(result, Err(DownloadError::Cancelled)) {
+                let completed = result.as_ref().ok();
+                let entry = NewHistoryEntry {
+                    url: job.item.url,
+                    video_id: job.item.id.clone(),
+                    title: job.item.title,
+                    output_path: completed.and_then(|c| c.output_path.clone()),
+                    format: job.options.output_extension().to_string(),
+                    file_size: completed.and_then(|c| c.file_size),
+                    duration: job.item.duration,
+                    success: result.is_ok(),
+                    error: result.err().map(|e| e.to_string()),
+                };
+
+                if let Err(e) = app.state::<AppState>().history.record(entry) {
+                    eprintln!("Failed to record download history: {}", e);
+                }
+            }
+
+            app.state::<AppState>().downloads.unregister(&id, &control);
         });
 
         tasks.push(task);
@@ -172,24 +329,238 @@ pub async fn start_downloads(
 
     // Emit downloads stopped event
     let _ = app.emit("downloads-stopped", ());
+}
+
+// List downloads left unfinished by a previous run and replay their last progress
+#[command]
+pub async fn get_unfinished_downloads(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<QueuedJob>, ErrorInfo> {
+    let jobs = state.queue.unfinished();
+
+    for job in &jobs {
+        if let Some(progress) = &job.last_progress {
+            let _ = app.emit("download-progress", progress);
+        }
+    }
+
+    Ok(jobs)
+}
+
+// Resume unfinished downloads from their saved options and partial files
+#[command]
+pub async fn resume_unfinished_downloads(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<(), ErrorInfo> {
+    let parallel = state
+        .settings
+        .lock()
+        .map(|s| s.parallel_downloads)
+        .map_err(|e| {
+            ErrorCode::StorageError.with_detail(format!("Failed to access settings state: {}", e))
+        })?;
+
+    // Skip anything that is already running again
+    let jobs = state
+        .queue
+        .find(&ids)
+        .into_iter()
+        .filter(|job| state.downloads.get(job.item.job_id()).is_none())
+        .collect();
+
+    run_downloads(&app, &state, jobs, parallel).await;
 
     Ok(())
 }
 
-// stop all downloads
+// Forget unfinished downloads the user chose not to resume
+#[command]
+pub async fn discard_unfinished_downloads(
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+) -> Result<(), ErrorInfo> {
+    state.queue.remove(&ids);
+    Ok(())
+}
+
+// Merge per-item overrides with the defaults from settings
+fn resolve_download_options(
+    item: &DownloadItem,
+    settings: &Settings,
+) -> Result<DownloadOptions, ErrorInfo> {
+    let preset_name = item.preset.as_ref().or(settings.default_preset.as_ref());
+
+    let preset = match preset_name {
+        Some(name) => Some(settings.find_preset(name).cloned().ok_or_else(|| {
+            ErrorCode::InvalidSettings.with_detail(format!("Unknown quality preset '{}'", name))
+        })?),
+        None => None,
+    };
+
+    let video = item.video.unwrap_or(settings.video);
+    video
+        .validate()
+        .map_err(|e| ErrorCode::InvalidSettings.with_detail(e))?;
+
+    let context = TemplateContext {
+        playlist: item.playlist_title.clone(),
+        index: item.playlist_index,
+        ..TemplateContext::default()
+    };
+    let output_template = to_ytdlp_template(
+        &settings.folder_template,
+        &settings.filename_template,
+        &context,
+    )
+    .map_err(|e| ErrorCode::InvalidSettings.with_detail(e))?;
+    let time_range =
+        TimeRange::from_item(item).map_err(|e| ErrorCode::InvalidSettings.with_detail(e))?;
+
+    let chapter_count = item.chapters.as_ref().map(|chapters| chapters.len() as u32);
+    if item.split_chapters && chapter_count == Some(0) {
+        return Err(ErrorCode::InvalidSettings.with_detail(format!(
+            "'{}' has no chapters to split by",
+            item.title.as_deref().unwrap_or(&item.id)
+        )));
+    }
+
+    Ok(DownloadOptions {
+        mode: item.mode.unwrap_or(settings.mode),
+        audio_format: item.audio_format.unwrap_or(settings.audio_format),
+        preset,
+        video,
+        retry: settings.retry,
+        output_template,
+        collision_policy: settings.collision_policy,
+        split_chapters: item.split_chapters,
+        chapter_count: chapter_count.filter(|_| item.split_chapters),
+        time_range,
+        sponsorblock: settings.sponsorblock.clone(),
+        loudness: settings.loudness,
+        tagging: settings.tagging.enabled.then(|| TagPlan {
+            // Mixes and channel uploads are not albums. Items queued before the
+            // kind was sent only carried a title for playlists.
+            album: item.playlist_title.clone().filter(|_| {
+                item.playlist_kind
+                    .is_none_or(|k| k == PlaylistKind::Playlist)
+            }),
+            track_number: item.playlist_index,
+            track_total: item.playlist_count,
+            default_genre: settings.tagging.default_genre.clone(),
+            overrides: item.tags.clone().unwrap_or_default(),
+        }),
+        cover_art: settings.cover_art,
+        lyrics: settings.lyrics.clone(),
+    })
+}
+
+// cancel a single download
 #[command]
-pub async fn stop_downloads(app: tauri::AppHandle) -> Result<(), String> {
-    set_stop_signal(true);
+pub async fn cancel_download(state: State<'_, AppState>, id: String) -> Result<(), ErrorInfo> {
+    if state.downloads.cancel(&id) {
+        Ok(())
+    } else {
+        Err(ErrorCode::NotFound.with_detail(format!("No active download with id {}", id)))
+    }
+}
+
+// cancel every queued and running download
+#[command]
+pub async fn cancel_all(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ErrorInfo> {
+    state.downloads.cancel_all();
     let _ = app.emit("downloads-stopping", ());
     Ok(())
 }
 
+// pause a running download, keeping its partial file
 #[command]
-pub async fn open_file(path: String) -> Result<(), String> {
+pub async fn pause_download(state: State<'_, AppState>, id: String) -> Result<(), ErrorInfo> {
+    match state.downloads.get(&id) {
+        Some(control) if control.pause() => Ok(()),
+        Some(_) => {
+            Err(ErrorCode::InvalidState.with_detail(format!("Download {} cannot be paused", id)))
+        }
+        None => Err(ErrorCode::NotFound.with_detail(format!("No active download with id {}", id))),
+    }
+}
+
+// resume a paused download from its partial file
+#[command]
+pub async fn resume_download(state: State<'_, AppState>, id: String) -> Result<(), ErrorInfo> {
+    match state.downloads.get(&id) {
+        Some(control) if control.resume() => Ok(()),
+        Some(_) => {
+            Err(ErrorCode::InvalidState.with_detail(format!("Download {} is not paused", id)))
+        }
+        None => Err(ErrorCode::NotFound.with_detail(format!("No active download with id {}", id))),
+    }
+}
+
+// list past downloads, newest first
+#[command]
+pub async fn list_history(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<HistoryEntry>, ErrorInfo> {
+    state
+        .history
+        .list(limit.unwrap_or(100), offset.unwrap_or(0))
+        .map_err(|e| ErrorCode::StorageError.with_detail(format!("Failed to load history: {}", e)))
+}
+
+// search past downloads by title, URL or video id
+#[command]
+pub async fn search_history(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<HistoryEntry>, ErrorInfo> {
+    state
+        .history
+        .search(&query, limit.unwrap_or(100))
+        .map_err(|e| {
+            ErrorCode::StorageError.with_detail(format!("Failed to search history: {}", e))
+        })
+}
+
+#[command]
+pub async fn clear_history(state: State<'_, AppState>) -> Result<(), ErrorInfo> {
+    state
+        .history
+        .clear()
+        .map_err(|e| ErrorCode::StorageError.with_detail(format!("Failed to clear history: {}", e)))
+}
+
+// forget which videos were downloaded, so archive mode skips nothing
+#[command]
+pub async fn clear_download_archive(state: State<'_, AppState>) -> Result<(), ErrorInfo> {
+    state.archive.clear().map_err(|e| {
+        ErrorCode::StorageError.with_detail(format!("Failed to clear download archive: {}", e))
+    })
+}
+
+// stop all downloads (kept for existing callers, same as cancel_all)
+#[command]
+pub async fn stop_downloads(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), ErrorInfo> {
+    cancel_all(app, state).await
+}
+
+#[command]
+pub async fn open_file(path: String) -> Result<(), ErrorInfo> {
     let path_buf = std::path::PathBuf::from(&path);
 
     if !path_buf.exists() {
-        return Err("File does not exist".to_string());
+        return Err(ErrorCode::OpenFailed.with_detail("File does not exist"));
     }
 
     #[cfg(target_os = "windows")]
@@ -197,44 +568,44 @@ pub async fn open_file(path: String) -> Result<(), String> {
         Command::new("cmd")
             .args(["/C", "start", "", &path])
             .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
+            .map_err(|e| {
+                ErrorCode::OpenFailed.with_detail(format!("Failed to open file: {}", e))
+            })?;
     }
 
     #[cfg(target_os = "macos")]
     {
-        Command::new("open")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
+        Command::new("open").arg(&path).spawn().map_err(|e| {
+            ErrorCode::OpenFailed.with_detail(format!("Failed to open file: {}", e))
+        })?;
     }
 
     #[cfg(target_os = "linux")]
     {
-        Command::new("xdg-open")
-            .arg(&path)
-            .spawn()
-            .map_err(|e| format!("Failed to open file: {}", e))?;
+        Command::new("xdg-open").arg(&path).spawn().map_err(|e| {
+            ErrorCode::OpenFailed.with_detail(format!("Failed to open file: {}", e))
+        })?;
     }
 
     Ok(())
 }
 
 #[command]
-pub async fn open_folder(path: String) -> Result<(), String> {
+pub async fn open_folder(path: String) -> Result<(), ErrorInfo> {
     let path_buf = std::path::PathBuf::from(&path);
     let is_file = path_buf.is_file();
 
     let folder_path = if is_file {
         path_buf
             .parent()
-            .ok_or("Cannot get parent directory")?
+            .ok_or_else(|| ErrorCode::OpenFailed.with_detail("Cannot get parent directory"))?
             .to_path_buf()
     } else {
         path_buf.clone()
     };
 
     if !folder_path.exists() {
-        return Err("Folder does not exist".to_string());
+        return Err(ErrorCode::OpenFailed.with_detail("Folder does not exist"));
     }
 
     #[cfg(target_os = "windows")]
@@ -243,12 +614,16 @@ pub async fn open_folder(path: String) -> Result<(), String> {
             Command::new("explorer")
                 .args(["/select,", &path])
                 .spawn()
-                .map_err(|e| format!("Failed to open folder: {}", e))?;
+                .map_err(|e| {
+                    ErrorCode::OpenFailed.with_detail(format!("Failed to open folder: {}", e))
+                })?;
         } else {
             Command::new("explorer")
                 .arg(&folder_path)
                 .spawn()
-                .map_err(|e| format!("Failed to open folder: {}", e))?;
+                .map_err(|e| {
+                    ErrorCode::OpenFailed.with_detail(format!("Failed to open folder: {}", e))
+                })?;
         }
     }
 
@@ -258,12 +633,16 @@ pub async fn open_folder(path: String) -> Result<(), String> {
             Command::new("open")
                 .args(["-R", &path])
                 .spawn()
-                .map_err(|e| format!("Failed to open folder: {}", e))?;
+                .map_err(|e| {
+                    ErrorCode::OpenFailed.with_detail(format!("Failed to open folder: {}", e))
+                })?;
         } else {
             Command::new("open")
                 .arg(&folder_path)
                 .spawn()
-                .map_err(|e| format!("Failed to open folder: {}", e))?;
+                .map_err(|e| {
+                    ErrorCode::OpenFailed.with_detail(format!("Failed to open folder: {}", e))
+                })?;
         }
     }
 
@@ -272,7 +651,9 @@ pub async fn open_folder(path: String) -> Result<(), String> {
         Command::new("xdg-open")
             .arg(&folder_path)
             .spawn()
-            .map_err(|e| format!("Failed to open folder: {}", e))?;
+            .map_err(|e| {
+                ErrorCode::OpenFailed.with_detail(format!("Failed to open folder: {}", e))
+            })?;
     }
 
     Ok(())
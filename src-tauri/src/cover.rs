@@ -0,0 +1,196 @@
+use crate::downloader::DownloadError;
+use crate::ffmpeg::{container_args, replace_with, run_ffmpeg, temp_output_path};
+use crate::formats::AudioFormat;
+use crate::registry::DownloadControl;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Name of the cover image optionally saved next to the audio
+pub const COVER_FILE_NAME: &str = "cover.jpg";
+
+fn default_square_crop() -> bool {
+    true
+}
+
+fn default_max_size() -> Option<u32> {
+    Some(1200)
+}
+
+fn default_jpeg_quality() -> u8 {
+    90
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverArtOptions {
+    // Crop the centre square, dropping the letterbox bars around album art
+    #[serde(default = "default_square_crop")]
+    pub square_crop: bool,
+    // Longest side in pixels; smaller images are left as they are
+    #[serde(default = "default_max_size")]
+    pub max_size: Option<u32>,
+    // 1 (smallest) to 100 (best)
+    #[serde(default = "default_jpeg_quality")]
+    pub jpeg_quality: u8,
+    // Also keep the image as cover.jpg in the album folder
+    #[serde(default)]
+    pub save_cover_file: bool,
+}
+
+impl Default for CoverArtOptions {
+    fn default() -> Self {
+        Self {
+            square_crop: default_square_crop(),
+            max_size: default_max_size(),
+            jpeg_quality: default_jpeg_quality(),
+            save_cover_file: false,
+        }
+    }
+}
+
+impl CoverArtOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=100).contains(&self.jpeg_quality) {
+            return Err("Cover JPEG quality must be between 1 and 100".to_string());
+        }
+
+        match self.max_size {
+            Some(size) if !(64..=4096).contains(&size) => {
+                Err("Cover size must be between 64 and 4096 pixels".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // ffmpeg filter chain for the crop and resize, if any
+    fn filter(&self) -> Option<String> {
+        let mut filters = Vec::new();
+
+        if self.square_crop {
+            // crop centres by default
+            filters.push("crop=w='min(iw,ih)':h='min(iw,ih)'".to_string());
+        }
+
+        if let Some(size) = self.max_size {
+            filters.push(format!(
+                "scale=w='min({s},iw)':h='min({s},ih)':force_original_aspect_ratio=decrease",
+                s = size
+            ));
+        }
+
+        (!filters.is_empty()).then(|| filters.join(","))
+    }
+
+    // mjpeg's -q:v runs from 2 (best) to 31 (worst)
+    fn qscale(&self) -> u8 {
+        let quality = self.jpeg_quality.clamp(1, 100) as u32;
+        (2 + (100 - quality) * 29 / 99) as u8
+    }
+}
+
+// Formats whose cover we can embed with ffmpeg as an attached picture.
+// Ogg stores covers in a tag ffmpeg cannot write, so yt-dlp embeds those as before.
+pub fn can_embed_with_ffmpeg(format: AudioFormat) -> bool {
+    matches!(
+        format,
+        AudioFormat::Mp3 | AudioFormat::M4a | AudioFormat::Flac
+    )
+}
+
+// The thumbnail yt-dlp wrote for a file, converted to JPEG by --convert-thumbnails
+pub fn thumbnail_for(staged_path: &Path) -> Option<PathBuf> {
+    Some(staged_path.with_extension("jpg")).filter(|path| path.exists())
+}
+
+// Crop, resize and re-compress the thumbnail into a new JPEG next to it
+pub async fn process_cover(
+    app: &AppHandle,
+    control: &DownloadControl,
+    thumbnail: &Path,
+    options: &CoverArtOptions,
+) -> Result<PathBuf, DownloadError> {
+    let output = thumbnail.with_extension("cover.jpg");
+
+    let mut args = vec!["-i".to_string(), thumbnail.to_string_lossy().to_string()];
+    if let Some(filter) = options.filter() {
+        args.extend(["-vf".to_string(), filter]);
+    }
+    args.extend([
+        "-frames:v".to_string(),
+        "1".to_string(),
+        "-c:v".to_string(),
+        "mjpeg".to_string(),
+        "-q:v".to_string(),
+        options.qscale().to_string(),
+        output.to_string_lossy().to_string(),
+    ]);
+
+    run_ffmpeg(app, &args, control).await?;
+    Ok(output)
+}
+
+// Attach the cover to an audio file without re-encoding the audio
+pub async fn embed_cover(
+    app: &AppHandle,
+    control: &DownloadControl,
+    path: &Path,
+    cover: &Path,
+    format: AudioFormat,
+) -> Result<(), DownloadError> {
+    let temp = temp_output_path(path);
+    let mut args = vec![
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+        "-i".to_string(),
+        cover.to_string_lossy().to_string(),
+        "-map".to_string(),
+        "0:a".to_string(),
+        "-map".to_string(),
+        "1:v".to_string(),
+        "-c".to_string(),
+        "copy".to_string(),
+        "-map_metadata".to_string(),
+        "0".to_string(),
+        "-disposition:v:0".to_string(),
+        "attached_pic".to_string(),
+    ];
+
+    if format == AudioFormat::Mp3 {
+        args.extend([
+            "-metadata:s:v".to_string(),
+            "title=Album cover".to_string(),
+            "-metadata:s:v".to_string(),
+            "comment=Cover (front)".to_string(),
+        ]);
+    }
+
+    args.extend(container_args(format));
+    args.push(temp.to_string_lossy().to_string());
+
+    run_ffmpeg(app, &args, control).await?;
+    replace_with(path, &temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qscale(jpeg_quality: u8) -> u8 {
+        CoverArtOptions {
+            jpeg_quality,
+            ..CoverArtOptions::default()
+        }
+        .qscale()
+    }
+
+    #[test]
+    fn jpeg_quality_maps_onto_the_mjpeg_scale() {
+        assert_eq!(qscale(100), 2);
+        assert_eq!(qscale(1), 31);
+        assert_eq!(qscale(90), 4);
+        // Out-of-range values are clamped rather than wrapping
+        assert_eq!(qscale(0), 31);
+        assert_eq!(qscale(255), 2);
+        assert!((1..100).all(|q| qscale(q) >= qscale(q + 1)));
+    }
+}
@@ -1,24 +1,35 @@
 use crate::binaries::BinaryManager;
+use crate::cover::{
+    can_embed_with_ffmpeg, embed_cover, process_cover, thumbnail_for, CoverArtOptions,
+    COVER_FILE_NAME,
+};
+use crate::error::{last_error_line, ErrorCode, ErrorInfo};
+use crate::filename::{
+    place_file, sanitize_relative_path, staging_dir, CollisionPolicy, Placement,
+};
+use crate::formats::{AudioFormat, DownloadMode, QualityPreset, VideoOptions};
+use crate::loudness::{apply_loudness, LoudnessOptions};
+use crate::lyrics::{embed_uslt, export_lyrics, find_subtitle, read_transcript, LyricsOptions};
+use crate::playlist::{parse_timestamp, Chapter, PlaylistKind};
+use crate::progress::{
+    metadata_args, parse_chapter_count, parse_chapter_track, parse_metadata_line,
+    parse_output_line, parse_progress_line, parse_sponsor_segments_line, progress_template_args,
+    sponsor_segments_args,
+};
+use crate::registry::DownloadControl;
+use crate::settings::RetryPolicy;
+use crate::sponsorblock::{SponsorBlockMode, SponsorBlockOptions, SponsorSegment};
+use crate::tagging::{apply_tags, TagOverrides, TagPlan, VideoMetadata};
+use crate::AppState;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
-use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command as TokioCommand;
-
-// Global stop signal
-static STOP_DOWNLOADS: AtomicBool = AtomicBool::new(false);
-
-// Function to set stop signal (called from commands module)
-pub fn set_stop_signal(stop: bool) {
-    STOP_DOWNLOADS.store(stop, Ordering::Relaxed);
-}
-
-// Function to check stop signal
-fn is_stop_requested() -> bool {
-    STOP_DOWNLOADS.load(Ordering::Relaxed)
-}
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{Child, Command as TokioCommand};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
@@ -30,9 +41,74 @@ pub struct DownloadProgress {
     pub downloaded: Option<String>,
     pub total_size: Option<String>,
     pub error: Option<String>,
+    pub error_info: Option<ErrorInfo>,
+    // Raw values behind the formatted strings above
+    pub speed_bps: Option<f64>,
+    pub eta_seconds: Option<u64>,
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    // The .part file yt-dlp is writing to
+    pub partial_file: Option<String>,
+    // Set on completion: the file yt-dlp wrote and its size in bytes
+    pub output_path: Option<String>,
+    pub file_size: Option<u64>,
+    // Set on completion when the file name was already taken
+    pub collision: Option<CollisionPolicy>,
+    // Set while splitting by chapters, once per track written
+    pub track: Option<TrackProgress>,
+    // Set on completion when SponsorBlock segments were cut out
+    pub removed_segments: Option<Vec<SponsorSegment>>,
+    // Which of our own post-processing steps is running, during Processing
+    pub stage: Option<ProcessingStage>,
 }
 
+impl DownloadProgress {
+    pub fn new(id: &str, status: DownloadStatus, progress: f64) -> Self {
+        Self {
+            id: id.to_string(),
+            status,
+            progress,
+            speed: None,
+            eta: None,
+            downloaded: None,
+            total_size: None,
+            error: None,
+            error_info: None,
+            speed_bps: None,
+            eta_seconds: None,
+            downloaded_bytes: None,
+            total_bytes: None,
+            partial_file: None,
+            output_path: None,
+            file_size: None,
+            collision: None,
+            track: None,
+            removed_segments: None,
+            stage: None,
+        }
+    }
+}
+
+// Post-processing run by the app after yt-dlp is done
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingStage {
+    Normalizing,
+    Tagging,
+    CoverArt,
+    Lyrics,
+}
+
+// A track written when splitting a video by chapters
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackProgress {
+    // 1-based, in chapter order
+    pub number: u32,
+    pub total: Option<u32>,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum DownloadStatus {
     Pending,
@@ -41,21 +117,249 @@ pub enum DownloadStatus {
     Completed,
     Error,
     Cancelled,
+    Paused,
+    Skipped,
+    // Waiting `next_in` seconds before retry number `attempt`
+    Retrying { attempt: u32, next_in: u64 },
 }
 
-#[derive(Debug)]
+// What a finished download produced
+#[derive(Debug, Clone, Default)]
+pub struct CompletedDownload {
+    pub output_path: Option<String>,
+    pub file_size: Option<u64>,
+    pub collision: Option<CollisionPolicy>,
+}
+
+#[derive(Debug, Clone)]
 pub enum DownloadError {
     InvalidPath,
     YtDlpNotFound,
-    DownloadFailed(String),
+    // yt-dlp exited with an error, classified from its stderr
+    DownloadFailed { code: ErrorCode, stderr: String },
     Cancelled,
     BinaryError(String),
 }
 
-#[derive(serde::Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadItem {
     pub id: String,
+    // Identifies this queue entry, so one video can be queued more than once
+    // with different output options. Older callers only sent the video id.
+    #[serde(default)]
+    pub job_id: Option<String>,
     pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub duration: Option<String>,
+    // Position in the playlist it was picked from, for naming templates
+    #[serde(default)]
+    pub playlist_title: Option<String>,
+    #[serde(default)]
+    pub playlist_index: Option<u32>,
+    // Kind of list it was picked from; only real playlists name an album
+    #[serde(default)]
+    pub playlist_kind: Option<PlaylistKind>,
+    // Size of that playlist, for "track N of M" tags
+    #[serde(default)]
+    pub playlist_count: Option<u32>,
+    // Write one file per chapter instead of a single file
+    #[serde(default)]
+    pub split_chapters: bool,
+    // The video's chapters; unset when they are not known
+    #[serde(default)]
+    pub chapters: Option<Vec<Chapter>>,
+    // Tags set by the user, replacing what would be derived from the video
+    #[serde(default)]
+    pub tags: Option<TagOverrides>,
+    // Only download this part of the video, e.g. "12:30" to "18:45"
+    #[serde(default)]
+    pub start_time: Option<String>,
+    #[serde(default)]
+    pub end_time: Option<String>,
+    // Overrides the format from settings for this item only
+    #[serde(default)]
+    pub audio_format: Option<AudioFormat>,
+    // Name of a quality preset from settings
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub mode: Option<DownloadMode>,
+    #[serde(default)]
+    pub video: Option<VideoOptions>,
+}
+
+impl DownloadItem {
+    // Key for the registry, the saved queue, progress events and the staging folder
+    pub fn job_id(&self) -> &str {
+        self.job_id.as_deref().unwrap_or(&self.id)
+    }
+}
+
+// Output options resolved from the item and settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadOptions {
+    pub mode: DownloadMode,
+    pub audio_format: AudioFormat,
+    pub preset: Option<QualityPreset>,
+    pub video: VideoOptions,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // yt-dlp output template relative to the download folder
+    #[serde(default = "default_output_template")]
+    pub output_template: String,
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    #[serde(default)]
+    pub split_chapters: bool,
+    // Number of tracks splitting will write, when the chapters are known
+    #[serde(default)]
+    pub chapter_count: Option<u32>,
+    #[serde(default)]
+    pub time_range: Option<TimeRange>,
+    #[serde(default)]
+    pub sponsorblock: SponsorBlockOptions,
+    #[serde(default)]
+    pub loudness: LoudnessOptions,
+    // Unset when tagging is turned off
+    #[serde(default)]
+    pub tagging: Option<TagPlan>,
+    #[serde(default)]
+    pub cover_art: CoverArtOptions,
+    #[serde(default)]
+    pub lyrics: LyricsOptions,
+}
+
+fn default_output_template() -> String {
+    "%(title)s.%(ext)s".to_string()
+}
+
+// Part of a video to download, in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: f64,
+    // Up to the end of the video when unset
+    pub end: Option<f64>,
+}
+
+impl TimeRange {
+    // Parse the item's start and end timestamps, checking them against its duration
+    pub fn from_item(item: &DownloadItem) -> Result<Option<Self>, String> {
+        if item.start_time.is_none() && item.end_time.is_none() {
+            return Ok(None);
+        }
+
+        let parse = |text: &String| {
+            parse_timestamp(text).ok_or_else(|| format!("Invalid timestamp '{}'", text))
+        };
+        let start = item
+            .start_time
+            .as_ref()
+            .map(parse)
+            .transpose()?
+            .unwrap_or(0.0);
+        let end = item.end_time.as_ref().map(parse).transpose()?;
+
+        if end.is_some_and(|end| end <= start) {
+            return Err("The end of the time range must be after its start".to_string());
+        }
+
+        let duration = item.duration.as_deref().and_then(parse_timestamp);
+        if let Some(duration) = duration {
+            if start >= duration || end.is_some_and(|end| end > duration) {
+                return Err(format!(
+                    "The time range falls outside the video, which is {} long",
+                    item.duration.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+
+        Ok(Some(Self { start, end }))
+    }
+
+    fn ytdlp_args(&self) -> Vec<String> {
+        let end = self
+            .end
+            .map(|end| end.to_string())
+            .unwrap_or_else(|| "inf".to_string());
+
+        vec![
+            "--download-sections".to_string(),
+            format!("*{}-{}", self.start, end),
+        ]
+    }
+}
+
+// File name of each chapter track, numbered in chapter order
+const CHAPTER_FILE_TEMPLATE: &str = "%(section_number)02d - %(section_title)s.%(ext)s";
+
+impl DownloadOptions {
+    fn format_args(&self) -> Vec<String> {
+        match self.mode {
+            DownloadMode::Audio => {
+                let mut args = vec![
+                    "--extract-audio".to_string(),
+                    "--audio-format".to_string(),
+                    self.audio_format.as_ytdlp_arg().to_string(),
+                ];
+                args.extend(self.quality_args());
+                args
+            }
+            DownloadMode::Video => self.video.ytdlp_args(),
+        }
+    }
+
+    // Spans of the video, in seconds, that are not in the finished file:
+    // whatever lies outside the time range, and SponsorBlock segments cut out
+    fn cut_spans(&self, removed_segments: Option<&[SponsorSegment]>) -> Vec<(f64, f64)> {
+        let mut cuts: Vec<(f64, f64)> = removed_segments
+            .unwrap_or_default()
+            .iter()
+            .map(|segment| (segment.start_time, segment.end_time))
+            .collect();
+
+        if let Some(range) = &self.time_range {
+            cuts.push((0.0, range.start));
+            if let Some(end) = range.end {
+                cuts.push((end, f64::INFINITY));
+            }
+        }
+
+        cuts
+    }
+
+    // Extension of the file this download produces
+    pub fn output_extension(&self) -> &'static str {
+        match self.mode {
+            DownloadMode::Audio => self.audio_format.extension(),
+            DownloadMode::Video => self.video.format.extension(),
+        }
+    }
+
+    fn supports_thumbnail(&self) -> bool {
+        match self.mode {
+            DownloadMode::Audio => self.audio_format.supports_thumbnail(),
+            DownloadMode::Video => self.video.format.supports_thumbnail(),
+        }
+    }
+
+    // Whether the app processes and embeds the cover itself instead of yt-dlp
+    fn embeds_own_cover(&self) -> bool {
+        self.mode == DownloadMode::Audio && can_embed_with_ffmpeg(self.audio_format)
+    }
+
+    fn writes_thumbnail(&self) -> bool {
+        self.embeds_own_cover() || self.cover_art.save_cover_file
+    }
+
+    fn quality_args(&self) -> Vec<String> {
+        match &self.preset {
+            // Bitrate settings are meaningless for lossless output
+            Some(preset) if !self.audio_format.is_lossless() => preset.ytdlp_args(),
+            _ => vec!["--audio-quality".to_string(), "0".to_string()], // best quality
+        }
+    }
 }
 
 impl std::fmt::Display for DownloadError {
@@ -63,7 +367,9 @@ impl std::fmt::Display for DownloadError {
         match self {
             DownloadError::InvalidPath => write!(f, "Invalid download path"),
             DownloadError::YtDlpNotFound => write!(f, "yt-dlp not found"),
-            DownloadError::DownloadFailed(msg) => write!(f, "Download failed: {}", msg),
+            DownloadError::DownloadFailed { code, stderr } => {
+                write!(f, "{}: {}", code.message(), last_error_line(stderr))
+            }
             DownloadError::Cancelled => write!(f, "Download cancelled"),
             DownloadError::BinaryError(msg) => write!(f, "Binary error: {}", msg),
         }
@@ -72,16 +378,79 @@ impl std::fmt::Display for DownloadError {
 
 impl std::error::Error for DownloadError {}
 
+impl DownloadError {
+    pub fn from_stderr(stderr: String) -> Self {
+        DownloadError::DownloadFailed {
+            code: ErrorCode::from_ytdlp_stderr(&stderr),
+            stderr,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DownloadError::InvalidPath => ErrorCode::InvalidPath,
+            DownloadError::YtDlpNotFound | DownloadError::BinaryError(_) => {
+                ErrorCode::YtDlpNotFound
+            }
+            DownloadError::DownloadFailed { code, .. } => *code,
+            DownloadError::Cancelled => ErrorCode::Cancelled,
+        }
+    }
+
+    pub fn info(&self) -> ErrorInfo {
+        let detail = match self {
+            DownloadError::DownloadFailed { stderr, .. } => Some(last_error_line(stderr)),
+            DownloadError::BinaryError(msg) => Some(msg.clone()),
+            _ => None,
+        };
+        ErrorInfo::new(self.code(), detail)
+    }
+}
+
+// Sent to the frontend as a structured ErrorInfo
+impl Serialize for DownloadError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.info().serialize(serializer)
+    }
+}
+
 pub async fn download_video(
     app: AppHandle,
-    id: String,
-    url: String,
+    item: &DownloadItem,
     download_folder: String,
-) -> Result<(), DownloadError> {
+    options: DownloadOptions,
+    control: Arc<DownloadControl>,
+    slots: Arc<Semaphore>,
+) -> Result<CompletedDownload, DownloadError> {
+    let staging = staging_dir(Path::new(&download_folder), item.job_id());
+    let result = run_download(app, item, &download_folder, options, control, slots).await;
+
+    // Pausing never returns from run_download, so the staging folder is kept
+    // while paused. After a failure it is only kept when a resume may succeed.
+    if let Err(e) = &result {
+        if !e.code().is_transient() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+    }
+
+    result
+}
+
+async fn run_download(
+    app: AppHandle,
+    item: &DownloadItem,
+    download_folder: &str,
+    options: DownloadOptions,
+    control: Arc<DownloadControl>,
+    slots: Arc<Semaphore>,
+) -> Result<CompletedDownload, DownloadError> {
+    let id = item.job_id().to_string();
+    let url = &item.url;
     println!("🚀 Starting download for: {}", url);
+    let staging = &staging_dir(Path::new(download_folder), &id);
 
-    // Check stop signal at start
-    if is_stop_requested() {
+    // Check cancellation at start
+    if control.is_cancelled() {
         return Err(DownloadError::Cancelled);
     }
 
@@ -95,48 +464,493 @@ pub async fn download_video(
     }
 
     // Validate download folder
-    if !Path::new(&download_folder).exists() {
-        std::fs::create_dir_all(&download_folder).map_err(|_| DownloadError::InvalidPath)?;
+    if !Path::new(download_folder).exists() {
+        std::fs::create_dir_all(download_folder).map_err(|_| DownloadError::InvalidPath)?;
     }
 
-    // Send initial status
-    emit_progress(
-        &app,
-        DownloadProgress {
-            id: id.clone(),
-            status: DownloadStatus::Downloading,
-            progress: 0.0,
-            speed: None,
-            eta: None,
-            downloaded: None,
-            total_size: None,
-            error: None,
-        },
-    );
+    // yt-dlp writes into a staging folder; the finished file is moved into place afterwards
+    let output_template = format!("{}/{}", staging.to_string_lossy(), options.output_template);
+    let mut attempt = 0;
+    // Held while yt-dlp runs, and given up while paused so queued jobs can start
+    let mut slot = None;
+
+    loop {
+        if slot.is_none() {
+            slot = Some(acquire_slot(&app, &control, &id, &slots).await?);
+            report_progress(
+                &app,
+                &control,
+                DownloadProgress::new(&id, DownloadStatus::Downloading, last_percent(&control)),
+            );
+        }
+
+        let mut cmd = build_command(&app, &yt_dlp_path, url, &output_template, &options);
+
+        let mut child = cmd.spawn().map_err(|e| {
+            DownloadError::BinaryError(format!("Failed to spawn yt-dlp process: {}", e))
+        })?;
+
+        // Handle stdout (where yt-dlp outputs progress and the final path)
+        let stdout_task = child.stdout.take().map(|stdout| {
+            let reader = BufReader::new(stdout);
+            let app_clone = app.clone();
+            let id_clone = id.clone();
+            let control_clone = control.clone();
+            let chapter_count = options.chapter_count;
+
+            tokio::spawn(async move {
+                let mut output = StagedOutput::default();
+                // yt-dlp's own count replaces this once it starts splitting
+                let mut track_total = chapter_count;
+                let mut lines = reader.lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    // Stop reporting once the process is being stopped
+                    if control_clone.is_cancelled() || control_clone.is_paused() {
+                        break;
+                    }
+
+                    if let Some(path) = parse_output_line(&line) {
+                        output.path = Some(path);
+                    } else if let Some(segments) = parse_sponsor_segments_line(&line) {
+                        output.removed_segments = Some(segments);
+                    } else if let Some(metadata) = parse_metadata_line(&line) {
+                        output.metadata = metadata;
+                    } else if let Some(total) = parse_chapter_count(&line) {
+                        track_total = Some(total);
+                    } else if let Some((number, path)) = parse_chapter_track(&line) {
+                        // Only reported here; the tracks are listed from disk once done
+                        report_progress(
+                            &app_clone,
+                            &control_clone,
+                            track_progress(&id_clone, number, track_total, Path::new(&path)),
+                        );
+                    } else if let Some(progress) = parse_progress_line(&line, &id_clone) {
+                        report_progress(&app_clone, &control_clone, progress);
+                    }
+                }
+                output
+            })
+        });
+
+        // Drain stderr while the process runs so a full pipe cannot stall it
+        let stderr_task = child.stderr.take().map(|mut stderr| {
+            tokio::spawn(async move {
+                let mut output = String::new();
+                let _ = stderr.read_to_string(&mut output).await;
+                output
+            })
+        });
+
+        let outcome = match monitor_process(&mut child, stderr_task, &control).await {
+            Err(e) if attempt < options.retry.max_retries && e.code().is_transient() => {
+                attempt += 1;
+                let delay = options.retry.delay_for(attempt);
+
+                report_progress(
+                    &app,
+                    &control,
+                    DownloadProgress {
+                        error: Some(e.to_string()),
+                        error_info: Some(e.info()),
+                        ..DownloadProgress::new(
+                            &id,
+                            DownloadStatus::Retrying {
+                                attempt,
+                                next_in: delay.as_secs(),
+                            },
+                            last_percent(&control),
+                        )
+                    },
+                );
+
+                sleep_unless_cancelled(&control, delay).await?;
+                continue;
+            }
+            result => result?,
+        };
+
+        match outcome {
+            RunOutcome::Finished(_) => {
+                // stdout closes with the process, so this returns promptly
+                let mut staged = match stdout_task {
+                    Some(task) => task.await.unwrap_or_default(),
+                    None => StagedOutput::default(),
+                };
+
+                if options.split_chapters {
+                    if let Some(path) = staged.path.as_deref() {
+                        staged.tracks = chapter_tracks(Path::new(path));
+                    }
+                }
+
+                if options.mode == DownloadMode::Audio && options.loudness.is_enabled() {
+                    report_progress(
+                        &app,
+                        &control,
+                        DownloadProgress {
+                            stage: Some(ProcessingStage::Normalizing),
+                            ..DownloadProgress::new(&id, DownloadStatus::Processing, 97.0)
+                        },
+                    );
+
+                    // Chapter tracks share an album gain measured over the unsplit file
+                    let album_source = staged
+                        .path
+                        .as_ref()
+                        .filter(|_| !staged.tracks.is_empty())
+                        .map(Path::new);
+                    apply_loudness(
+                        &app,
+                        &control,
+                        &options.loudness,
+                        &staged.files(),
+                        album_source,
+                        options.audio_format,
+                        options.preset.as_ref().map(|preset| &preset.quality),
+                    )
+                    .await?;
+                }
+
+                if let Some(plan) = options
+                    .tagging
+                    .as_ref()
+                    .filter(|_| options.mode == DownloadMode::Audio)
+                {
+                    report_progress(
+                        &app,
+                        &control,
+                        DownloadProgress {
+                            stage: Some(ProcessingStage::Tagging),
+                            ..DownloadProgress::new(&id, DownloadStatus::Processing, 98.0)
+                        },
+                    );
+
+                    apply_tags(
+                        &app,
+                        &control,
+                        plan,
+                        &staged.metadata,
+                        &staged.files(),
+                        !staged.tracks.is_empty(),
+                        options.audio_format,
+                    )
+                    .await?;
+                }
+
+                let thumbnail = staged
+                    .path
+                    .as_deref()
+                    .map(Path::new)
+                    .and_then(thumbnail_for)
+                    .filter(|_| options.writes_thumbnail());
+                if let Some(thumbnail) = thumbnail {
+                    report_progress(
+                        &app,
+                        &control,
+                        DownloadProgress {
+                            stage: Some(ProcessingStage::CoverArt),
+                            ..DownloadProgress::new(&id, DownloadStatus::Processing, 99.0)
+                        },
+                    );
+
+                    let cover =
+                        process_cover(&app, &control, &thumbnail, &options.cover_art).await?;
+                    if options.embeds_own_cover() {
+                        for file in staged.files() {
+                            embed_cover(&app, &control, &file, &cover, options.audio_format)
+                                .await?;
+                        }
+                    }
+                    if options.cover_art.save_cover_file {
+                        staged.cover = Some(cover);
+                    }
+                }
+
+                let subtitle = staged
+                    .path
+                    .as_deref()
+                    .filter(|_| options.lyrics.is_enabled())
+                    .and_then(|path| find_subtitle(Path::new(path), &options.lyrics.languages));
+                if let (Some(staged_path), Some((subtitle, language))) = (&staged.path, subtitle) {
+                    report_progress(
+                        &app,
+                        &control,
+                        DownloadProgress {
+                            stage: Some(ProcessingStage::Lyrics),
+                            ..DownloadProgress::new(&id, DownloadStatus::Processing, 99.0)
+                        },
+                    );
+
+                    let lyrics_error = |e: std::io::Error| {
+                        DownloadError::from_stderr(format!("Failed to write lyrics: {}", e))
+                    };
+                    let cuts = options.cut_spans(staged.removed_segments.as_deref());
+                    let exported =
+                        export_lyrics(Path::new(staged_path), &subtitle, &options.lyrics, &cuts)
+                            .map_err(lyrics_error)?;
+                    staged.sidecars.extend(exported);
+
+                    // A whole-video transcript only belongs in an unsplit file
+                    let embeds_lyrics = options.lyrics.embed
+                        && options.mode == DownloadMode::Audio
+                        && options.audio_format == AudioFormat::Mp3
+                        && staged.tracks.is_empty();
+                    if embeds_lyrics {
+                        let transcript = read_transcript(&subtitle, &cuts).map_err(lyrics_error)?;
+                        embed_uslt(Path::new(staged_path), &transcript, &language)
+                            .map_err(lyrics_error)?;
+                    }
+                }
 
-    let output_template = format!("{}/%(title)s.%(ext)s", download_folder);
-
-    let mut cmd = TokioCommand::new(&yt_dlp_path);
-    cmd.args(&[
-        "--extract-audio",
-        "--audio-format",
-        "mp3",
-        "--audio-quality",
-        "0", // best quality
-        "--embed-thumbnail",
-        "--add-metadata",
-        "--no-warnings",
-        "--newline",  // Force newlines for better parsing
-        "--progress", // Enable progress reporting
-        "-o",
-        &output_template,
-        &url,
-    ])
-    .stdout(Stdio::piped())
-    .stderr(Stdio::piped());
+                let placement = finalize_output(
+                    &staged,
+                    staging,
+                    download_folder,
+                    options.collision_policy,
+                    &item.id,
+                )?;
+                let output_path = Some(placement.path.to_string_lossy().to_string());
+                let file_size = placement.size;
+
+                report_progress(
+                    &app,
+                    &control,
+                    DownloadProgress {
+                        output_path: output_path.clone(),
+                        file_size,
+                        collision: placement.collision,
+                        removed_segments: staged.removed_segments.clone(),
+                        ..DownloadProgress::new(&id, DownloadStatus::Completed, 100.0)
+                    },
+                );
+                return Ok(CompletedDownload {
+                    output_path,
+                    file_size,
+                    collision: placement.collision,
+                });
+            }
+            RunOutcome::Paused => {
+                // The .part file stays on disk; the next run resumes it with --continue
+                slot = None;
+                report_progress(
+                    &app,
+                    &control,
+                    DownloadProgress::new(&id, DownloadStatus::Paused, last_percent(&control)),
+                );
+            }
+        }
+    }
+}
+
+// Files yt-dlp reported writing into the staging folder
+#[derive(Debug, Default)]
+struct StagedOutput {
+    path: Option<String>,
+    // Chapter tracks, when splitting by chapters
+    tracks: Vec<PathBuf>,
+    removed_segments: Option<Vec<SponsorSegment>>,
+    metadata: VideoMetadata,
+    // Processed cover to save as cover.jpg
+    cover: Option<PathBuf>,
+    // Files that go next to the output, such as lyrics
+    sidecars: Vec<PathBuf>,
+}
+
+impl StagedOutput {
+    // The files that make up the download: the chapter tracks, or the single file
+    fn files(&self) -> Vec<PathBuf> {
+        if self.tracks.is_empty() {
+            self.path.iter().map(PathBuf::from).collect()
+        } else {
+            self.tracks.clone()
+        }
+    }
+}
+
+// Where the finished download ended up: the file, or the folder of chapter tracks
+struct FinalOutput {
+    path: PathBuf,
+    size: Option<u64>,
+    collision: Option<CollisionPolicy>,
+}
+
+// Move finished files out of staging under sanitized names, then drop the staging folder
+fn finalize_output(
+    staged: &StagedOutput,
+    staging: &Path,
+    download_folder: &str,
+    policy: CollisionPolicy,
+    id: &str,
+) -> Result<FinalOutput, DownloadError> {
+    let move_file = |staged_path: &Path| -> Result<Placement, DownloadError> {
+        let relative = staged_path
+            .strip_prefix(staging)
+            .unwrap_or_else(|_| Path::new(staged_path.file_name().unwrap_or_default()));
+        let target = Path::new(download_folder).join(sanitize_relative_path(relative));
+
+        place_file(staged_path, &target, policy, id)
+            .map_err(|e| DownloadError::from_stderr(format!("Failed to move download: {}", e)))
+    };
+
+    let output = if staged.tracks.is_empty() {
+        let staged_path = staged.path.as_ref().ok_or_else(|| {
+            DownloadError::from_stderr("yt-dlp did not report the output file".to_string())
+        })?;
+        let placement = move_file(Path::new(staged_path))?;
+
+        FinalOutput {
+            size: std::fs::metadata(&placement.path).ok().map(|m| m.len()),
+            path: placement.path,
+            collision: placement.collision,
+        }
+    } else {
+        // The unsplit file is left in staging and removed with it
+        let placements = staged
+            .tracks
+            .iter()
+            .map(|track| move_file(track))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        FinalOutput {
+            path: placements[0]
+                .path
+                .parent()
+                .unwrap_or(Path::new(download_folder))
+                .to_path_buf(),
+            size: placements
+                .iter()
+                .map(|p| std::fs::metadata(&p.path).ok().map(|m| m.len()))
+                .sum(),
+            collision: placements.iter().find_map(|p| p.collision),
+        }
+    };
+
+    // Sidecars follow the file they belong to, including any rename
+    for sidecar in &staged.sidecars {
+        let target = if staged.tracks.is_empty() {
+            sidecar_target(sidecar, staged.path.as_deref().map(Path::new), &output.path)
+        } else {
+            sidecar
+                .file_name()
+                .map(|name| output.path.join(sanitize_relative_path(Path::new(name))))
+        };
+
+        if let Some(target) = target {
+            if let Err(e) = std::fs::rename(sidecar, &target) {
+                eprintln!("Failed to save {}: {}", target.display(), e);
+            }
+        }
+    }
+
+    // The first cover saved in a folder is kept, as later items are usually from the same album.
+    // Loose files in the download folder itself share no album, so they get no cover file.
+    let cover_folder = if staged.tracks.is_empty() {
+        output
+            .path
+            .parent()
+            .filter(|folder| *folder != Path::new(download_folder))
+    } else {
+        Some(output.path.as_path())
+    };
+    if let (Some(cover), Some(folder)) = (&staged.cover, cover_folder) {
+        let target = folder.join(COVER_FILE_NAME);
+        if !target.exists() {
+            if let Err(e) = std::fs::rename(cover, &target) {
+                eprintln!("Failed to save {}: {}", target.display(), e);
+            }
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(staging);
+    Ok(output)
+}
+
+// "Song.lrc" next to staged "Song.mp3" becomes "Song (1).lrc" next to final "Song (1).mp3"
+fn sidecar_target(
+    sidecar: &Path,
+    staged_file: Option<&Path>,
+    final_file: &Path,
+) -> Option<PathBuf> {
+    let staged_stem = staged_file?.file_stem()?.to_string_lossy().to_string();
+    let final_stem = final_file.file_stem()?.to_string_lossy().to_string();
+    let suffix = sidecar
+        .file_name()?
+        .to_string_lossy()
+        .strip_prefix(&staged_stem)?
+        .to_string();
+
+    Some(final_file.with_file_name(format!("{}{}", final_stem, suffix)))
+}
+
+// The chapter tracks yt-dlp split the staged file into, in chapter order.
+// They are listed from disk, since yt-dlp reports only the unsplit file.
+fn chapter_tracks(staged_file: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(tracks_folder(staged_file)) else {
+        return Vec::new();
+    };
+
+    let mut tracks: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension() == staged_file.extension())
+        .collect();
+    // By number rather than name, as "100 - ..." sorts before "11 - ..."
+    tracks.sort_by_key(|track| (track_number(track), track.clone()));
+    tracks
+}
+
+// Track names start with the zero-padded chapter number
+fn track_number(track: &Path) -> Option<u32> {
+    let stem = track.file_stem()?.to_str()?;
+    stem.split_once(" - ")?.0.parse().ok()
+}
+
+// "Song.mp3" is split into "Song/01 - Intro.mp3", ... (see ytdlp_args)
+fn tracks_folder(staged_file: &Path) -> PathBuf {
+    staged_file.with_extension("")
+}
+
+fn track_progress(id: &str, number: u32, total: Option<u32>, path: &Path) -> DownloadProgress {
+    let progress = match total {
+        Some(total) if total > 0 => 95.0 + 5.0 * number.min(total) as f64 / total as f64,
+        _ => 95.0,
+    };
+    let title = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    DownloadProgress {
+        track: Some(TrackProgress {
+            number,
+            total,
+            title,
+        }),
+        ..DownloadProgress::new(id, DownloadStatus::Processing, progress.min(99.0))
+    }
+}
+
+// How a monitored process ended, if it was not cancelled or failed
+pub enum RunOutcome {
+    // Everything the process wrote to stderr
+    Finished(String),
+    Paused,
+}
+
+fn build_command(
+    app: &AppHandle,
+    yt_dlp_path: &Path,
+    url: &str,
+    output_template: &str,
+    options: &DownloadOptions,
+) -> TokioCommand {
+    let mut cmd = TokioCommand::new(yt_dlp_path);
+    cmd.args(ytdlp_args(url, output_template, options))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
 
     // Get bundled ffmpeg path and set it in environment if available
-    if let Ok(ffmpeg_path) = BinaryManager::get_binary_path(&app, "ffmpeg") {
+    if let Ok(ffmpeg_path) = BinaryManager::get_binary_path(app, "ffmpeg") {
         if ffmpeg_path.exists() {
             // Add ffmpeg directory to PATH for yt-dlp to find it
             if let Some(ffmpeg_dir) = ffmpeg_path.parent() {
@@ -154,198 +968,335 @@ pub async fn download_video(
         }
     }
 
-    let mut child = cmd.spawn().map_err(|e| {
-        DownloadError::DownloadFailed(format!("Failed to spawn yt-dlp process: {}", e))
-    })?;
+    cmd
+}
 
-    // Handle stdout (where yt-dlp outputs progress)
-    if let Some(stdout) = child.stdout.take() {
-        let reader = BufReader::new(stdout);
-        let app_clone = app.clone();
-        let id_clone = id.clone();
+// Everything passed to yt-dlp, kept free of the app handle so it can be tested
+fn ytdlp_args(url: &str, output_template: &str, options: &DownloadOptions) -> Vec<String> {
+    let mut args = options.format_args();
 
-        tokio::spawn(async move {
-            let mut lines = reader.lines();
-            while let Ok(Some(line)) = lines.next_line().await {
-                // Check stop signal during progress parsing
-                if is_stop_requested() {
-                    break;
-                }
+    if options.writes_thumbnail() {
+        args.extend(["--write-thumbnail", "--convert-thumbnails", "jpg"].map(String::from));
+    }
 
-                println!("📝 yt-dlp: {}", line);
-                if let Some(progress) = parse_progress_line(&line, &id_clone) {
-                    println!("📊 Parsed progress: {:?}", progress);
-                    emit_progress(&app_clone, progress);
-                }
-            }
-        });
+    if options.supports_thumbnail() && !options.embeds_own_cover() {
+        args.push("--embed-thumbnail".to_string());
+    }
+
+    args.extend(
+        [
+            "--add-metadata",
+            "--no-warnings",
+            "--newline",  // Force newlines for better parsing
+            "--progress", // Enable progress reporting
+        ]
+        .map(String::from),
+    );
+    // Resume .part files left by a pause. Sections are cut by ffmpeg, which
+    // cannot resume, so a paused time range starts over from a clean file.
+    args.push(if options.time_range.is_some() {
+        "--no-continue".to_string()
+    } else {
+        "--continue".to_string()
+    });
+    args.extend(progress_template_args());
+    args.extend(["-o".to_string(), output_template.to_string()]);
+
+    if options.tagging.is_some() {
+        args.extend(metadata_args());
+    }
+
+    if options.lyrics.is_enabled() {
+        args.extend(options.lyrics.ytdlp_args());
+    }
+
+    if options.sponsorblock.is_enabled() {
+        args.extend(options.sponsorblock.ytdlp_args());
+        if options.sponsorblock.mode == SponsorBlockMode::Remove {
+            args.extend(sponsor_segments_args());
+        }
+    }
+
+    if let Some(range) = &options.time_range {
+        args.extend(range.ytdlp_args());
+        // Cut video exactly at the range instead of the nearest keyframe
+        if options.mode == DownloadMode::Video {
+            args.push("--force-keyframes-at-cuts".to_string());
+        }
     }
 
-    // Monitor process and stop signal
+    if options.split_chapters {
+        // Tracks go in a folder named after the unsplit file; see tracks_folder
+        let tracks_folder = output_template.trim_end_matches(".%(ext)s");
+        args.extend([
+            "--split-chapters".to_string(),
+            "-o".to_string(),
+            format!("chapter:{}/{}", tracks_folder, CHAPTER_FILE_TEMPLATE),
+        ]);
+    }
+
+    args.push(url.to_string());
+    args
+}
+
+// Monitor a yt-dlp or ffmpeg process until it exits, is paused or is cancelled
+pub async fn monitor_process(
+    child: &mut Child,
+    stderr_task: Option<JoinHandle<String>>,
+    control: &DownloadControl,
+) -> Result<RunOutcome, DownloadError> {
     loop {
-        if is_stop_requested() {
-            println!("🛑 Stop signal received, killing process for: {}", id);
+        if control.is_cancelled() {
             let _ = child.kill().await;
             return Err(DownloadError::Cancelled);
         }
 
+        if control.is_paused() {
+            let _ = child.kill().await;
+            return Ok(RunOutcome::Paused);
+        }
+
         match child.try_wait() {
             Ok(Some(status)) => {
                 // Process finished
-                if status.success() && !is_stop_requested() {
-                    emit_progress(
-                        &app,
-                        DownloadProgress {
-                            id,
-                            status: DownloadStatus::Completed,
-                            progress: 100.0,
-                            speed: None,
-                            eta: None,
-                            downloaded: None,
-                            total_size: None,
-                            error: None,
-                        },
-                    );
-                    return Ok(());
-                } else if is_stop_requested() {
+                if control.is_cancelled() {
                     return Err(DownloadError::Cancelled);
-                } else {
-                    // Process failed
-                    let output = child
-                        .wait_with_output()
-                        .await
-                        .map_err(|e| DownloadError::DownloadFailed(e.to_string()))?;
-                    let error_msg = String::from_utf8_lossy(&output.stderr).to_string();
-                    return Err(DownloadError::DownloadFailed(error_msg));
                 }
+
+                let stderr = match stderr_task {
+                    Some(task) => task.await.unwrap_or_default(),
+                    None => String::new(),
+                };
+                return if status.success() {
+                    Ok(RunOutcome::Finished(stderr))
+                } else {
+                    Err(DownloadError::from_stderr(stderr))
+                };
             }
             Ok(None) => {
                 // Process still running, wait a bit
                 tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             }
             Err(e) => {
-                return Err(DownloadError::DownloadFailed(e.to_string()));
+                return Err(DownloadError::from_stderr(e.to_string()));
             }
         }
     }
 }
 
-fn parse_progress_line(line: &str, id: &str) -> Option<DownloadProgress> {
-    let line = line.trim();
-    println!("🔍 Parsing line: '{}'", line); // Debug each line
+// Wait for one of the parallel download slots. A job paused while it waits
+// reports Paused and waits to be resumed before queueing again.
+async fn acquire_slot(
+    app: &AppHandle,
+    control: &DownloadControl,
+    id: &str,
+    slots: &Arc<Semaphore>,
+) -> Result<OwnedSemaphorePermit, DownloadError> {
+    loop {
+        if wait_while_paused(control).await? {
+            report_progress(
+                app,
+                control,
+                DownloadProgress::new(id, DownloadStatus::Pending, last_percent(control)),
+            );
+        }
+
+        let permit = tokio::select! {
+            // The semaphore is never closed
+            permit = slots.clone().acquire_owned() => permit.ok(),
+            _ = wait_until_stopped(control) => None,
+        };
 
-    // Handle post-processing
-    if line.contains("[ffmpeg]") || line.contains("Converting") || line.contains("Deleting") {
-        return Some(DownloadProgress {
-            id: id.to_string(),
-            status: DownloadStatus::Processing,
-            progress: 95.0,
-            speed: None,
-            eta: None,
-            downloaded: None,
-            total_size: None,
-            error: None,
-        });
+        if control.is_cancelled() {
+            return Err(DownloadError::Cancelled);
+        }
+
+        match permit {
+            Some(permit) if !control.is_paused() => return Ok(permit),
+            _ => report_progress(
+                app,
+                control,
+                DownloadProgress::new(id, DownloadStatus::Paused, last_percent(control)),
+            ),
+        }
     }
+}
 
-    // Parse various download progress formats:
-    // [download]  45.0% of 10.23MiB at 1.2MiB/s ETA 00:07
-    // [download] 100% of 10.23MiB in 00:08
-    // 45.0%|1.2MiB/s|00:07 (from custom template)
+async fn wait_until_stopped(control: &DownloadControl) {
+    while !control.is_cancelled() && !control.is_paused() {
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+}
 
-    let mut progress_value = None;
-    let mut speed = None;
-    let mut eta = None;
-    let mut total_size = None;
+// Wait until a paused download is resumed. Returns whether it had been paused.
+pub async fn wait_while_paused(control: &DownloadControl) -> Result<bool, DownloadError> {
+    let mut was_paused = false;
 
-    // Try to find percentage first
-    if let Some(percent_match) = extract_percentage(line) {
-        progress_value = Some(percent_match);
-        println!("📊 Found percentage: {}%", percent_match);
+    while control.is_paused() {
+        was_paused = true;
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
 
-    // Extract other info if it's a download line
-    if line.contains("[download]") || line.contains("%") {
-        // Extract size info "45.0% of 10.23MiB"
-        if let Some(of_pos) = line.find(" of ") {
-            let after_of = &line[of_pos + 4..];
-            if let Some(space_pos) = after_of.find(' ') {
-                total_size = Some(after_of[..space_pos].trim().to_string());
-            } else if let Some(in_pos) = after_of.find(" in ") {
-                total_size = Some(after_of[..in_pos].trim().to_string());
-            }
-        }
+    if control.is_cancelled() {
+        return Err(DownloadError::Cancelled);
+    }
 
-        // Extract speed "at 1.2MiB/s"
-        if let Some(at_pos) = line.find(" at ") {
-            let after_at = &line[at_pos + 4..];
-            if let Some(space_pos) = after_at.find(' ') {
-                speed = Some(after_at[..space_pos].trim().to_string());
-            }
+    Ok(was_paused)
+}
+
+// Sleep before a retry, waking early if the download is cancelled
+async fn sleep_unless_cancelled(
+    control: &DownloadControl,
+    delay: std::time::Duration,
+) -> Result<(), DownloadError> {
+    let deadline = tokio::time::Instant::now() + delay;
+
+    while tokio::time::Instant::now() < deadline {
+        if control.is_cancelled() {
+            return Err(DownloadError::Cancelled);
         }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
 
-        // Extract ETA "ETA 00:07"
-        if let Some(eta_pos) = line.find("ETA ") {
-            let after_eta = &line[eta_pos + 4..];
-            let eta_str = after_eta.split_whitespace().next().unwrap_or("").trim();
-            if !eta_str.is_empty() {
-                eta = Some(eta_str.to_string());
-            }
+    Ok(())
+}
+
+fn last_percent(control: &DownloadControl) -> f64 {
+    control.last_progress().map(|p| p.progress).unwrap_or(0.0)
+}
+
+fn report_progress(app: &AppHandle, control: &DownloadControl, progress: DownloadProgress) {
+    control.record_progress(&progress);
+    emit_progress(app, progress);
+}
+
+// Emit a progress event and keep the persisted queue in step with it
+pub fn emit_progress(app: &AppHandle, progress: DownloadProgress) {
+    if let Some(state) = app.try_state::<AppState>() {
+        state.queue.record(&progress);
+    }
+
+    if let Err(e) = app.emit("download-progress", &progress) {
+        eprintln!("Failed to emit progress: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(split_chapters: bool) -> DownloadOptions {
+        DownloadOptions {
+            mode: DownloadMode::Audio,
+            audio_format: AudioFormat::Mp3,
+            preset: None,
+            video: VideoOptions::default(),
+            retry: RetryPolicy::default(),
+            output_template: default_output_template(),
+            collision_policy: CollisionPolicy::default(),
+            split_chapters,
+            chapter_count: None,
+            time_range: None,
+            sponsorblock: SponsorBlockOptions::default(),
+            loudness: LoudnessOptions::default(),
+            tagging: None,
+            cover_art: CoverArtOptions::default(),
+            lyrics: LyricsOptions::default(),
         }
+    }
 
-        if let Some(progress) = progress_value {
-            let status = if progress >= 100.0 {
-                DownloadStatus::Processing
-            } else {
-                DownloadStatus::Downloading
-            };
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        folder
+    }
 
-            return Some(DownloadProgress {
-                id: id.to_string(),
-                status,
-                progress,
-                speed,
-                eta,
-                downloaded: None,
-                total_size,
-                error: None,
-            });
+    // Fill in the yt-dlp fields used by the output templates
+    fn render(template: &str, section: Option<(u32, &str)>) -> String {
+        let mut path = template
+            .replace("%(title)s", "Song")
+            .replace("%(ext)s", "mp3");
+        if let Some((number, title)) = section {
+            path = path
+                .replace("%(section_number)02d", &format!("{:02}", number))
+                .replace("%(section_title)s", title);
         }
+        path
     }
 
-    None
-}
+    #[test]
+    fn chapter_tracks_are_found_where_ytdlp_writes_them() {
+        let staging = temp_folder("chapter-tracks");
+        let output_template = format!("{}/{}", staging.display(), default_output_template());
+        let args = ytdlp_args("https://youtu.be/x", &output_template, &options(true));
 
-fn extract_percentage(line: &str) -> Option<f64> {
-    // Look for patterns like "45.0%", "100%", "  67.5% "
-    let mut chars = line.chars().peekable();
-    let mut number_str = String::new();
-    let mut found_digit = false;
+        assert!(args.iter().any(|arg| arg == "--split-chapters"));
+        let chapter_template = args
+            .iter()
+            .find_map(|arg| arg.strip_prefix("chapter:"))
+            .expect("no chapter output template");
 
-    while let Some(ch) = chars.next() {
-        if ch.is_ascii_digit() || ch == '.' {
-            number_str.push(ch);
-            found_digit = true;
-        } else if found_digit && ch == '%' {
-            // Found percentage, try to parse
-            if let Ok(percentage) = number_str.parse::<f64>() {
-                return Some(percentage);
-            }
-            // Reset for next potential percentage
-            number_str.clear();
-            found_digit = false;
-        } else if found_digit && !ch.is_whitespace() {
-            // Not a percentage, reset
-            number_str.clear();
-            found_digit = false;
+        // What yt-dlp leaves behind: the unsplit file and one file per chapter
+        let staged_file = PathBuf::from(render(&output_template, None));
+        std::fs::write(&staged_file, b"unsplit").unwrap();
+        for (number, title) in [(2, "Verse"), (100, "Outro"), (1, "Intro"), (11, "Bridge")] {
+            let track = PathBuf::from(render(chapter_template, Some((number, title))));
+            std::fs::create_dir_all(track.parent().unwrap()).unwrap();
+            std::fs::write(&track, title).unwrap();
         }
+
+        let names: Vec<String> = chapter_tracks(&staged_file)
+            .iter()
+            .map(|track| track.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "01 - Intro.mp3",
+                "02 - Verse.mp3",
+                "11 - Bridge.mp3",
+                "100 - Outro.mp3"
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&staging);
     }
 
-    None
-}
+    #[test]
+    fn finalize_keeps_chapter_tracks_and_drops_the_unsplit_file() {
+        let root = temp_folder("finalize-tracks");
+        let download_folder = root.join("downloads");
+        let staging = staging_dir(&download_folder, "abc");
+        let staged_file = staging.join("Song.mp3");
+        std::fs::create_dir_all(tracks_folder(&staged_file)).unwrap();
+        std::fs::write(&staged_file, b"unsplit").unwrap();
 
-fn emit_progress(app: &AppHandle, progress: DownloadProgress) {
-    if let Err(e) = app.emit("download-progress", &progress) {
-        eprintln!("Failed to emit progress: {}", e);
+        let mut staged = StagedOutput {
+            path: Some(staged_file.to_string_lossy().to_string()),
+            ..StagedOutput::default()
+        };
+        for name in ["01 - Intro.mp3", "02 - Verse.mp3"] {
+            let track = tracks_folder(&staged_file).join(name);
+            std::fs::write(&track, name).unwrap();
+        }
+        staged.tracks = chapter_tracks(&staged_file);
+
+        let output = finalize_output(
+            &staged,
+            &staging,
+            &download_folder.to_string_lossy(),
+            CollisionPolicy::Rename,
+            "abc",
+        )
+        .unwrap();
+
+        assert_eq!(output.path, download_folder.join("Song"));
+        assert!(output.path.join("01 - Intro.mp3").is_file());
+        assert!(output.path.join("02 - Verse.mp3").is_file());
+        assert!(!download_folder.join("Song.mp3").exists());
+        assert!(!staging.exists());
+
+        let _ = std::fs::remove_dir_all(&root);
     }
 }
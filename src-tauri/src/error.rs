@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+
+// Machine-readable error codes shared by downloads and playlist fetches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidUrl,
+    InvalidRange,
+    InvalidPath,
+    InvalidSettings,
+    YtDlpNotFound,
+    FfmpegMissing,
+    VideoUnavailable,
+    PrivateVideo,
+    AgeRestricted,
+    GeoBlocked,
+    MembersOnly,
+    RateLimited,
+    NetworkError,
+    DiskFull,
+    PermissionDenied,
+    ParseError,
+    FetchTimedOut,
+    FetchCancelled,
+    Cancelled,
+    NotFound,
+    InvalidState,
+    StorageError,
+    OpenFailed,
+    Unknown,
+}
+
+impl ErrorCode {
+    // Classify a failed yt-dlp run from its stderr. Permanent causes are
+    // checked first, since their messages can also mention HTTP errors.
+    pub fn from_ytdlp_stderr(stderr: &str) -> Self {
+        const RULES: [(ErrorCode, &[&str]); 11] = [
+            (ErrorCode::PrivateVideo, &["private video"]),
+            (
+                ErrorCode::AgeRestricted,
+                &[
+                    "sign in to confirm your age",
+                    "age-restricted",
+                    "inappropriate for some users",
+                ],
+            ),
+            (
+                ErrorCode::GeoBlocked,
+                &[
+                    "not available in your country",
+                    "geo restrict",
+                    "geo-restrict",
+                ],
+            ),
+            (
+                ErrorCode::MembersOnly,
+                &["members-only", "join this channel"],
+            ),
+            (
+                ErrorCode::VideoUnavailable,
+                &[
+                    "video unavailable",
+                    "has been removed",
+                    "no longer available",
+                    "account associated with this video has been terminated",
+                    "copyright",
+                ],
+            ),
+            (
+                ErrorCode::DiskFull,
+                &["no space left on device", "errno 28"],
+            ),
+            (
+                ErrorCode::PermissionDenied,
+                &["permission denied", "errno 13"],
+            ),
+            (
+                ErrorCode::FfmpegMissing,
+                &[
+                    "ffmpeg not found",
+                    "ffprobe and ffmpeg not found",
+                    "ffmpeg is not installed",
+                ],
+            ),
+            (
+                ErrorCode::InvalidUrl,
+                &["unsupported url", "is not a valid url"],
+            ),
+            (
+                ErrorCode::RateLimited,
+                &[
+                    "http error 429",
+                    "too many requests",
+                    "confirm you're not a bot",
+                ],
+            ),
+            (
+                ErrorCode::NetworkError,
+                &[
+                    "http error 5",
+                    "connection reset",
+                    "connection refused",
+                    "timed out",
+                    "temporary failure in name resolution",
+                    "network is unreachable",
+                    "remote end closed connection",
+                    "incompleteread",
+                    // "Giving up after 10 fragment retries"
+                    "fragment retries",
+                ],
+            ),
+        ];
+
+        let stderr = stderr.to_lowercase();
+        RULES
+            .iter()
+            .find(|(_, needles)| needles.iter().any(|n| stderr.contains(n)))
+            .map(|(code, _)| *code)
+            .or_else(|| {
+                // "fragment 12 not found, unable to continue", reported once
+                // yt-dlp's own fragment retries ran out
+                stderr
+                    .lines()
+                    .any(is_missing_fragment)
+                    .then_some(ErrorCode::NetworkError)
+            })
+            .unwrap_or(ErrorCode::Unknown)
+    }
+
+    // Failures that may succeed if tried again later
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ErrorCode::RateLimited | ErrorCode::NetworkError)
+    }
+
+    // Wrap this code and a description of what went wrong for the frontend
+    pub fn with_detail(self, detail: impl Into<String>) -> ErrorInfo {
+        ErrorInfo::new(self, Some(detail.into()))
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            ErrorCode::InvalidUrl => "This is not a supported YouTube URL",
+            ErrorCode::InvalidRange => "The playlist item range is not valid",
+            ErrorCode::InvalidPath => "The download folder cannot be used",
+            ErrorCode::InvalidSettings => "The settings are not valid",
+            ErrorCode::YtDlpNotFound => "yt-dlp could not be found",
+            ErrorCode::FfmpegMissing => "ffmpeg could not be found",
+            ErrorCode::VideoUnavailable => "This video is unavailable",
+            ErrorCode::PrivateVideo => "This video is private",
+            ErrorCode::AgeRestricted => "This video is age-restricted",
+            ErrorCode::GeoBlocked => "This video is not available in your country",
+            ErrorCode::MembersOnly => "This video is for channel members only",
+            ErrorCode::RateLimited => "YouTube is rate limiting requests",
+            ErrorCode::NetworkError => "A network error interrupted the download",
+            ErrorCode::DiskFull => "The disk is full",
+            ErrorCode::PermissionDenied => "Permission denied while writing the file",
+            ErrorCode::ParseError => "yt-dlp returned data that could not be read",
+            ErrorCode::FetchTimedOut => "Fetching the playlist took too long",
+            ErrorCode::FetchCancelled => "Playlist fetch cancelled",
+            ErrorCode::Cancelled => "Download cancelled",
+            ErrorCode::NotFound => "That download or fetch is no longer running",
+            ErrorCode::InvalidState => "That action is not possible right now",
+            ErrorCode::StorageError => "Saved app data could not be read or written",
+            ErrorCode::OpenFailed => "The file or folder could not be opened",
+            ErrorCode::Unknown => "The download failed",
+        }
+    }
+
+    // Suggested fix to show next to the message
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            ErrorCode::InvalidUrl => Some("Paste a link to a YouTube video, playlist or mix."),
+            ErrorCode::InvalidRange => Some("Start at 1 or later and end at or after the start."),
+            ErrorCode::InvalidPath | ErrorCode::PermissionDenied => {
+                Some("Choose a download folder you can write to in Settings.")
+            }
+            ErrorCode::YtDlpNotFound | ErrorCode::FfmpegMissing => {
+                Some("Restart the app so the bundled tools are extracted again.")
+            }
+            ErrorCode::VideoUnavailable => Some("The video may have been removed by the uploader."),
+            ErrorCode::PrivateVideo => {
+                Some("Ask the uploader to make the video public or unlisted.")
+            }
+            ErrorCode::AgeRestricted | ErrorCode::MembersOnly => {
+                Some("This video needs a signed-in account and cannot be downloaded.")
+            }
+            ErrorCode::GeoBlocked => Some("Try again from a region where the video is available."),
+            ErrorCode::RateLimited => Some("Wait a few minutes, or lower parallel downloads."),
+            ErrorCode::NetworkError => Some("Check your internet connection and try again."),
+            ErrorCode::DiskFull => Some("Free up space or choose another download folder."),
+            ErrorCode::ParseError => Some("Updating the app may bring a newer yt-dlp."),
+            ErrorCode::FetchTimedOut => {
+                Some("Fetch a smaller range, or raise the fetch timeout in Settings.")
+            }
+            ErrorCode::InvalidSettings => {
+                Some("Fix the setting named in the details and save again.")
+            }
+            ErrorCode::StorageError => Some("Check that the app data folder is writable."),
+            ErrorCode::OpenFailed => Some("The file may have been moved or deleted."),
+            ErrorCode::Cancelled
+            | ErrorCode::FetchCancelled
+            | ErrorCode::NotFound
+            | ErrorCode::InvalidState
+            | ErrorCode::Unknown => None,
+        }
+    }
+}
+
+// The JSON shape every download and playlist error is sent to the frontend in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorInfo {
+    pub code: ErrorCode,
+    pub message: String,
+    pub hint: Option<String>,
+    // Raw output behind the error, for the details view
+    pub detail: Option<String>,
+}
+
+impl ErrorInfo {
+    pub fn new(code: ErrorCode, detail: Option<String>) -> Self {
+        Self {
+            code,
+            message: code.message().to_string(),
+            hint: code.hint().map(|h| h.to_string()),
+            detail,
+        }
+    }
+}
+
+fn is_missing_fragment(line: &str) -> bool {
+    line.split("fragment ").skip(1).any(|rest| {
+        let number = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        number > 0 && rest[number..].starts_with(" not found")
+    })
+}
+
+// Last non-empty line of a process's stderr, usually the actual error
+pub fn last_error_line(stderr: &str) -> String {
+    stderr
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or(stderr)
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ytdlp_errors_are_classified() {
+        let cases = [
+            (
+                "ERROR: [youtube] abc: Private video. Sign in if you've been granted access",
+                ErrorCode::PrivateVideo,
+            ),
+            (
+                "ERROR: [youtube] abc: Sign in to confirm your age. This video may be inappropriate for some users.",
+                ErrorCode::AgeRestricted,
+            ),
+            ("ERROR: [youtube] abc: Video unavailable", ErrorCode::VideoUnavailable),
+            (
+                "ERROR: unable to download video data: HTTP Error 429: Too Many Requests",
+                ErrorCode::RateLimited,
+            ),
+            (
+                "ERROR: unable to download video data: HTTP Error 503: Service Unavailable",
+                ErrorCode::NetworkError,
+            ),
+            (
+                "ERROR: fragment 12 not found, unable to continue",
+                ErrorCode::NetworkError,
+            ),
+            ("ERROR: [Errno 28] No space left on device", ErrorCode::DiskFull),
+            (
+                "ERROR: Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path using --ffmpeg-location",
+                ErrorCode::FfmpegMissing,
+            ),
+            ("ERROR: something else went wrong", ErrorCode::Unknown),
+        ];
+
+        for (stderr, code) in cases {
+            assert_eq!(ErrorCode::from_ytdlp_stderr(stderr), code, "{}", stderr);
+        }
+    }
+
+    #[test]
+    fn permanent_causes_win_over_http_errors() {
+        let stderr = "ERROR: [youtube] abc: Private video\nHTTP Error 503: Service Unavailable";
+        let code = ErrorCode::from_ytdlp_stderr(stderr);
+        assert_eq!(code, ErrorCode::PrivateVideo);
+        assert!(!code.is_transient());
+    }
+
+    #[test]
+    fn fragment_progress_is_not_a_failure() {
+        let stderr = "[download] Got fragment 3 of 10\nERROR: something else went wrong";
+        assert_eq!(ErrorCode::from_ytdlp_stderr(stderr), ErrorCode::Unknown);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::binaries::BinaryManager;
+use crate::downloader::{monitor_process, wait_while_paused, DownloadError, RunOutcome};
+use crate::error::ErrorCode;
+use crate::formats::AudioFormat;
+use crate::registry::DownloadControl;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::AppHandle;
+use tokio::io::AsyncReadExt;
+use tokio::process::Command as TokioCommand;
+
+// Run the bundled ffmpeg to completion and return its stderr, where it logs
+// everything including filter measurements. Killed if the download is cancelled.
+// Pausing also kills it; the step runs again from its untouched input on resume.
+pub async fn run_ffmpeg(
+    app: &AppHandle,
+    args: &[String],
+    control: &DownloadControl,
+) -> Result<String, DownloadError> {
+    let ffmpeg_path = BinaryManager::get_binary_path(app, "ffmpeg")
+        .ok()
+        .filter(|path| path.exists())
+        .ok_or_else(|| DownloadError::DownloadFailed {
+            code: ErrorCode::FfmpegMissing,
+            stderr: String::new(),
+        })?;
+
+    loop {
+        let mut child = TokioCommand::new(&ffmpeg_path)
+            .args(["-hide_banner", "-nostdin", "-y"])
+            .args(args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| DownloadError::BinaryError(format!("Failed to spawn ffmpeg: {}", e)))?;
+
+        let stderr_task = child.stderr.take().map(|mut stderr| {
+            tokio::spawn(async move {
+                let mut output = String::new();
+                let _ = stderr.read_to_string(&mut output).await;
+                output
+            })
+        });
+
+        match monitor_process(&mut child, stderr_task, control).await? {
+            RunOutcome::Finished(stderr) => return Ok(stderr),
+            RunOutcome::Paused => {
+                wait_while_paused(control).await?;
+            }
+        }
+    }
+}
+
+// Sibling path ffmpeg writes to before it replaces the original,
+// e.g. "Song.mp3" -> "Song.tmp.mp3" so ffmpeg still picks the right muxer
+pub fn temp_output_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    match path.extension() {
+        Some(ext) => path.with_file_name(format!("{}.tmp.{}", stem, ext.to_string_lossy())),
+        None => path.with_file_name(format!("{}.tmp", stem)),
+    }
+}
+
+// Replace `path` with the file ffmpeg wrote to `temp`
+pub fn replace_with(path: &Path, temp: &Path) -> Result<(), DownloadError> {
+    std::fs::rename(temp, path).map_err(|e| {
+        let _ = std::fs::remove_file(temp);
+        DownloadError::from_stderr(format!("Failed to replace {}: {}", path.display(), e))
+    })
+}
+
+// Rewrite a file's tags without re-encoding; existing tags are kept unless replaced
+pub async fn write_tags(
+    app: &AppHandle,
+    control: &DownloadControl,
+    path: &Path,
+    format: AudioFormat,
+    tags: &[(&str, String)],
+) -> Result<(), DownloadError> {
+    let temp = temp_output_path(path);
+    let mut args = vec!["-i".to_string(), path.to_string_lossy().to_string()];
+    args.extend(stream_map_args(format));
+    args.extend([
+        "-c".to_string(),
+        "copy".to_string(),
+        "-map_metadata".to_string(),
+        "0".to_string(),
+    ]);
+
+    for (key, value) in tags {
+        args.push("-metadata".to_string());
+        args.push(format!("{}={}", key, value));
+    }
+
+    args.extend(container_args(format));
+    args.push(temp.to_string_lossy().to_string());
+
+    run_ffmpeg(app, &args, control).await?;
+    replace_with(path, &temp)
+}
+
+// Keep embedded cover art where the container stores it as a stream.
+// Ogg keeps it in a tag instead, which ffmpeg cannot write back as a stream.
+pub fn stream_map_args(format: AudioFormat) -> Vec<String> {
+    let mut args = vec!["-map".to_string(), "0:a".to_string()];
+    if matches!(
+        format,
+        AudioFormat::Mp3 | AudioFormat::M4a | AudioFormat::Flac
+    ) {
+        args.extend(["-map".to_string(), "0:v?".to_string()]);
+    }
+    args
+}
+
+// Muxer flags so custom tags such as ReplayGain survive
+pub fn container_args(format: AudioFormat) -> Vec<String> {
+    match format {
+        AudioFormat::Mp3 => vec!["-id3v2_version".to_string(), "3".to_string()],
+        AudioFormat::M4a => vec!["-movflags".to_string(), "+use_metadata_tags".to_string()],
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+// Folder inside the download folder where yt-dlp writes before files are moved into place
+const STAGING_DIR: &str = ".incomplete";
+
+// Longest file or folder name we write, in bytes; most file systems allow 255
+const MAX_COMPONENT_LEN: usize = 200;
+
+// Highest "(n)" tried before giving up on finding a free name
+const MAX_RENAME_SUFFIX: u32 = 9999;
+
+// Names Windows refuses to use as files, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// What to do when the finished file's name is already taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionPolicy {
+    // Keep the existing file and discard the new download
+    Skip,
+    Overwrite,
+    // "Title (1).mp3", "Title (2).mp3", ...
+    #[default]
+    Rename,
+    // "Title [videoid].mp3"
+    AppendId,
+}
+
+// Where a finished file ended up, and the policy used if its name was taken
+#[derive(Debug, Clone)]
+pub struct Placement {
+    pub path: PathBuf,
+    pub collision: Option<CollisionPolicy>,
+}
+
+// Make a single file or folder name safe on Windows, macOS and Linux
+pub fn sanitize_component(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .filter(|c| !is_emoji(*c))
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    // Windows drops trailing dots and spaces, which would make names clash
+    let mut name = replaced
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', ' '])
+        .to_string();
+
+    if name.is_empty() || name.chars().all(|c| c == '_' || c == '.') {
+        name = "untitled".to_string();
+    }
+
+    let stem = name.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES
+        .iter()
+        .any(|reserved| stem.eq_ignore_ascii_case(reserved))
+    {
+        name.insert(stem.len(), '_');
+    }
+
+    truncate_component(&name)
+}
+
+// Sanitize every component of a relative path, dropping anything that would leave its parent
+pub fn sanitize_relative_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(sanitize_component(&part.to_string_lossy())),
+            _ => None,
+        })
+        .collect()
+}
+
+// Per-download staging folder; keeping the id in the path lets --continue find the .part file
+pub fn staging_dir(download_folder: &Path, video_id: &str) -> PathBuf {
+    download_folder
+        .join(STAGING_DIR)
+        .join(sanitize_component(video_id))
+}
+
+// Move a finished file from staging to its place in the download folder,
+// applying the collision policy if the name is already taken
+pub fn place_file(
+    source: &Path,
+    target: &Path,
+    policy: CollisionPolicy,
+    video_id: &str,
+) -> std::io::Result<Placement> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if !target.exists() {
+        fs::rename(source, target)?;
+        return Ok(Placement {
+            path: target.to_path_buf(),
+            collision: None,
+        });
+    }
+
+    let path = match policy {
+        CollisionPolicy::Skip => {
+            fs::remove_file(source)?;
+            target.to_path_buf()
+        }
+        CollisionPolicy::Overwrite => {
+            fs::rename(source, target)?;
+            target.to_path_buf()
+        }
+        CollisionPolicy::Rename => {
+            let path = free_path(target, |n| format!("({})", n))?;
+            fs::rename(source, &path)?;
+            path
+        }
+        CollisionPolicy::AppendId => {
+            let with_id = with_suffix(target, &format!("[{}]", video_id));
+            // The same video downloaded twice still gets a free name
+            let path = if with_id.exists() {
+                free_path(target, |n| format!("[{}] ({})", video_id, n))?
+            } else {
+                with_id
+            };
+            fs::rename(source, &path)?;
+            path
+        }
+    };
+
+    Ok(Placement {
+        path,
+        collision: Some(policy),
+    })
+}
+
+fn free_path(target: &Path, suffix: impl Fn(u32) -> String) -> std::io::Result<PathBuf> {
+    (1..=MAX_RENAME_SUFFIX)
+        .map(|n| with_suffix(target, &suffix(n)))
+        .find(|path| !path.exists())
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("No free file name left for {}", target.display()),
+            )
+        })
+}
+
+// "dir/Title.mp3" + "(1)" -> "dir/Title (1).mp3". A long title is shortened
+// before the suffix goes on, so the suffix always survives.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+
+    let reserved = suffix.len() + 1 + ext.as_ref().map_or(0, |e| e.len() + 1);
+    let stem = truncate_on_char(&stem, MAX_COMPONENT_LEN.saturating_sub(reserved))
+        .trim_end_matches(['.', ' ']);
+
+    let name = match ext {
+        Some(ext) => format!("{} {}.{}", stem, suffix, ext),
+        None => format!("{} {}", stem, suffix),
+    };
+    path.with_file_name(name)
+}
+
+// Shorten an over-long name on a char boundary, keeping a short extension intact
+fn truncate_component(name: &str) -> String {
+    if name.len() <= MAX_COMPONENT_LEN {
+        return name.to_string();
+    }
+
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && ext.len() <= 5 => (stem, Some(ext)),
+        _ => (name, None),
+    };
+    let budget = MAX_COMPONENT_LEN - ext.map(|e| e.len() + 1).unwrap_or(0);
+    let stem = truncate_on_char(stem, budget).trim_end_matches(['.', ' ']);
+
+    match ext {
+        Some(ext) => format!("{}.{}", stem, ext),
+        None => stem.to_string(),
+    }
+}
+
+// The longest prefix of `text` that fits in `max` bytes without splitting a char
+fn truncate_on_char(text: &str, max: usize) -> &str {
+    let mut end = max.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+// Emoji break some players and older file systems, so they are dropped from names
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF // pictographs, emoticons, flags
+            | 0x2600..=0x27BF // misc symbols and dingbats
+            | 0xFE00..=0xFE0F // variation selectors
+            | 0x200D // zero-width joiner
+            | 0xE0020..=0xE007F // tag sequences
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn free_path_counts_past_taken_names() {
+        let folder = temp_folder("free-path");
+        let target = folder.join("Song.mp3");
+        fs::write(&target, b"").unwrap();
+        fs::write(folder.join("Song (1).mp3"), b"").unwrap();
+
+        let path = free_path(&target, |n| format!("({})", n)).unwrap();
+        assert_eq!(path, folder.join("Song (2).mp3"));
+
+        let _ = fs::remove_dir_all(&folder);
+    }
+
+    #[test]
+    fn long_multibyte_names_keep_their_suffix() {
+        let folder = temp_folder("long-names");
+        // 90 CJK characters are 270 bytes, more than a name may hold
+        let title = "曲".repeat(90);
+        let target = folder.join(sanitize_component(&format!("{}.mp3", title)));
+        fs::write(&target, b"").unwrap();
+
+        let mut taken = vec![target.clone()];
+        for policy in [CollisionPolicy::Rename, CollisionPolicy::AppendId] {
+            // Each policy collides twice, so it needs two distinct free names
+            for _ in 0..2 {
+                let source = folder.join("staged.mp3");
+                fs::write(&source, b"").unwrap();
+                let placement = place_file(&source, &target, policy, "abc").unwrap();
+
+                let name = placement.path.file_name().unwrap().to_string_lossy();
+                assert!(name.len() <= MAX_COMPONENT_LEN, "{}", name);
+                assert!(name.ends_with(".mp3"), "{}", name);
+                assert!(!taken.contains(&placement.path), "{}", name);
+                taken.push(placement.path);
+            }
+        }
+
+        let names: Vec<String> = taken[1..]
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert!(names[0].ends_with(" (1).mp3"));
+        assert!(names[1].ends_with(" (2).mp3"));
+        assert!(names[2].ends_with(" [abc].mp3"));
+        assert!(names[3].ends_with(" [abc] (1).mp3"));
+
+        let _ = fs::remove_dir_all(&folder);
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    #[default]
+    Mp3,
+    #[serde(alias = "aac")]
+    M4a,
+    Opus,
+    Flac,
+    Wav,
+    #[serde(alias = "ogg")]
+    Vorbis,
+}
+
+impl AudioFormat {
+    // Value passed to yt-dlp's --audio-format
+    pub fn as_ytdlp_arg(&self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::M4a => "m4a",
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "wav",
+            AudioFormat::Vorbis => "vorbis",
+        }
+    }
+
+    // File extension yt-dlp writes for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Vorbis => "ogg",
+            other => other.as_ytdlp_arg(),
+        }
+    }
+
+    pub fn is_lossless(&self) -> bool {
+        matches!(self, AudioFormat::Flac | AudioFormat::Wav)
+    }
+
+    // yt-dlp refuses to embed cover art into WAV files
+    pub fn supports_thumbnail(&self) -> bool {
+        !matches!(self, AudioFormat::Wav)
+    }
+
+    // ffmpeg arguments re-encoding to this format at roughly the quality yt-dlp used
+    pub fn ffmpeg_encoder_args(&self, quality: Option<&AudioQuality>) -> Vec<String> {
+        let codec = match self {
+            AudioFormat::Mp3 => "libmp3lame",
+            AudioFormat::M4a => "aac",
+            AudioFormat::Opus => "libopus",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Wav => "pcm_s16le",
+            AudioFormat::Vorbis => "libvorbis",
+        };
+        let mut args = vec!["-c:a".to_string(), codec.to_string()];
+
+        let quality = match (self, quality) {
+            (AudioFormat::Flac | AudioFormat::Wav, _) => None,
+            (AudioFormat::Mp3, Some(AudioQuality::Vbr { level })) => {
+                Some(("-q:a", (*level).min(9).to_string()))
+            }
+            (AudioFormat::Vorbis, Some(AudioQuality::Vbr { level })) => {
+                Some(("-q:a", (10 - (*level).min(10)).to_string()))
+            }
+            (_, Some(AudioQuality::Cbr { bitrate })) => Some(("-b:a", format!("{}k", bitrate))),
+            // Other encoders only take a bitrate; scale it from the VBR level
+            (_, Some(AudioQuality::Vbr { level })) => Some((
+                "-b:a",
+                format!("{}k", 320 - 24 * u16::from((*level).min(10))),
+            )),
+            (AudioFormat::Mp3, None) => Some(("-q:a", "0".to_string())),
+            (AudioFormat::Vorbis, None) => Some(("-q:a", "10".to_string())),
+            (_, None) => Some(("-b:a", "256k".to_string())),
+        };
+
+        if let Some((flag, value)) = quality {
+            args.push(flag.to_string());
+            args.push(value);
+        }
+
+        args
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum AudioQuality {
+    // Constant bitrate in kbps
+    Cbr { bitrate: u16 },
+    // ffmpeg VBR level, 0 (best) to 10 (worst)
+    Vbr { level: u8 },
+}
+
+impl AudioQuality {
+    // Value passed to yt-dlp's --audio-quality
+    pub fn as_ytdlp_arg(&self) -> String {
+        match self {
+            AudioQuality::Cbr { bitrate } => format!("{}K", bitrate),
+            AudioQuality::Vbr { level } => level.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QualityPreset {
+    pub name: String,
+    pub quality: AudioQuality,
+    #[serde(default)]
+    pub mono: bool,
+}
+
+impl QualityPreset {
+    pub fn new(name: &str, quality: AudioQuality, mono: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            quality,
+            mono,
+        }
+    }
+
+    // yt-dlp arguments selecting this preset's quality
+    pub fn ytdlp_args(&self) -> Vec<String> {
+        let mut args = vec!["--audio-quality".to_string(), self.quality.as_ytdlp_arg()];
+
+        if self.mono {
+            args.push("--postprocessor-args".to_string());
+            args.push("ExtractAudio:-ac 1".to_string());
+        }
+
+        args
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Preset name cannot be empty".to_string());
+        }
+
+        match self.quality {
+            AudioQuality::Cbr { bitrate } if !(32..=320).contains(&bitrate) => Err(format!(
+                "Preset '{}': bitrate must be between 32 and 320 kbps",
+                self.name
+            )),
+            AudioQuality::Vbr { level } if level > 10 => Err(format!(
+                "Preset '{}': VBR level must be between 0 and 10",
+                self.name
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn default_quality_presets() -> Vec<QualityPreset> {
+    vec![
+        QualityPreset::new("Podcast 96k mono", AudioQuality::Cbr { bitrate: 96 }, true),
+        QualityPreset::new("Standard 192k", AudioQuality::Cbr { bitrate: 192 }, false),
+        QualityPreset::new("Archive 320k", AudioQuality::Cbr { bitrate: 320 }, false),
+        QualityPreset::new("Best VBR", AudioQuality::Vbr { level: 0 }, false),
+    ]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadMode {
+    #[default]
+    Audio,
+    Video,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoFormat {
+    #[default]
+    Mp4,
+    Webm,
+    Mkv,
+}
+
+impl VideoFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VideoFormat::Mp4 => "mp4",
+            VideoFormat::Webm => "webm",
+            VideoFormat::Mkv => "mkv",
+        }
+    }
+
+    // yt-dlp cannot embed cover art into WebM containers
+    pub fn supports_thumbnail(&self) -> bool {
+        !matches!(self, VideoFormat::Webm)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct VideoOptions {
+    #[serde(default)]
+    pub format: VideoFormat,
+    // Maximum height in pixels, e.g. 720 or 1080
+    #[serde(default)]
+    pub max_resolution: Option<u32>,
+}
+
+impl VideoOptions {
+    // yt-dlp arguments selecting and packaging the video streams
+    pub fn ytdlp_args(&self) -> Vec<String> {
+        let selector = match self.max_resolution {
+            Some(height) => format!("bv*[height<={h}]+ba/b[height<={h}]/b", h = height),
+            None => "bv*+ba/b".to_string(),
+        };
+
+        let mut args = vec!["-f".to_string(), selector];
+
+        // Prefer streams that fit the target container to avoid re-encoding
+        match self.format {
+            VideoFormat::Mp4 => args.extend(["-S".to_string(), "ext:mp4:m4a".to_string()]),
+            VideoFormat::Webm => args.extend(["-S".to_string(), "ext:webm:webm".to_string()]),
+            VideoFormat::Mkv => {}
+        }
+
+        let ext = self.format.extension().to_string();
+        args.extend([
+            "--merge-output-format".to_string(),
+            ext.clone(),
+            "--remux-video".to_string(),
+            ext,
+        ]);
+
+        args
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self.max_resolution {
+            Some(height) if !(144..=4320).contains(&height) => {
+                Err("Maximum resolution must be between 144p and 4320p".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
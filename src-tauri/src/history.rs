@@ -0,0 +1,178 @@
+use crate::settings::get_settings_path;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub url: String,
+    pub video_id: String,
+    pub title: Option<String>,
+    pub output_path: Option<String>,
+    pub format: String,
+    pub file_size: Option<u64>,
+    pub duration: Option<String>,
+    // Unix timestamp in seconds
+    pub finished_at: i64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+// Everything needed to record a finished job; id and timestamp are filled in on insert
+#[derive(Debug, Clone)]
+pub struct NewHistoryEntry {
+    pub url: String,
+    pub video_id: String,
+    pub title: Option<String>,
+    pub output_path: Option<String>,
+    pub format: String,
+    pub file_size: Option<u64>,
+    pub duration: Option<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+const SELECT_COLUMNS: &str = "SELECT id, url, video_id, title, output_path, format, file_size, \
+     duration, finished_at, success, error FROM history";
+
+// Download history kept in history.db next to settings.json
+#[derive(Default)]
+pub struct HistoryDb {
+    conn: Mutex<Option<Connection>>,
+}
+
+impl HistoryDb {
+    pub fn open(&self, app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let settings_path = get_settings_path(app_handle)?;
+        let db_path = settings_path
+            .parent()
+            .ok_or("Failed to resolve config directory")?
+            .join("history.db");
+
+        let conn = Connection::open(db_path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                url TEXT NOT NULL,
+                video_id TEXT NOT NULL,
+                title TEXT,
+                output_path TEXT,
+                format TEXT NOT NULL,
+                file_size INTEGER,
+                duration TEXT,
+                finished_at INTEGER NOT NULL,
+                success INTEGER NOT NULL,
+                error TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_history_video_id ON history (video_id);
+            CREATE INDEX IF NOT EXISTS idx_history_finished_at ON history (finished_at);",
+        )?;
+
+        *self.conn.lock().map_err(|e| e.to_string())? = Some(conn);
+        Ok(())
+    }
+
+    pub fn record(&self, entry: NewHistoryEntry) -> Result<(), Box<dyn std::error::Error>> {
+        let finished_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO history (url, video_id, title, output_path, format, file_size,
+                    duration, finished_at, success, error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    entry.url,
+                    entry.video_id,
+                    entry.title,
+                    entry.output_path,
+                    entry.format,
+                    entry.file_size.map(|s| s as i64),
+                    entry.duration,
+                    finished_at,
+                    entry.success,
+                    entry.error,
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    // Most recent entries first
+    pub fn list(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<HistoryEntry>, Box<dyn std::error::Error>> {
+        self.with_conn(|conn| {
+            let sql = format!(
+                "{} ORDER BY finished_at DESC, id DESC LIMIT ?1 OFFSET ?2",
+                SELECT_COLUMNS
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![limit, offset], entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    // Match the query against title, URL and video id
+    pub fn search(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<HistoryEntry>, Box<dyn std::error::Error>> {
+        let pattern = format!("%{}%", escape_like(query.trim()));
+
+        self.with_conn(|conn| {
+            let sql = format!(
+                "{} WHERE title LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\' \
+                 OR video_id LIKE ?1 ESCAPE '\\' ORDER BY finished_at DESC, id DESC LIMIT ?2",
+                SELECT_COLUMNS
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params![pattern, limit], entry_from_row)?;
+            rows.collect()
+        })
+    }
+
+    pub fn clear(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.with_conn(|conn| {
+            conn.execute("DELETE FROM history", [])?;
+            Ok(())
+        })
+    }
+
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        let guard = self.conn.lock().map_err(|e| e.to_string())?;
+        let conn = guard.as_ref().ok_or("History database is not open")?;
+        Ok(f(conn)?)
+    }
+}
+
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        video_id: row.get(2)?,
+        title: row.get(3)?,
+        output_path: row.get(4)?,
+        format: row.get(5)?,
+        file_size: row.get::<_, Option<i64>>(6)?.map(|s| s as u64),
+        duration: row.get(7)?,
+        finished_at: row.get(8)?,
+        success: row.get(9)?,
+        error: row.get(10)?,
+    })
+}
+
+fn escape_like(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
@@ -0,0 +1,268 @@
+use crate::downloader::DownloadError;
+use crate::ffmpeg::{
+    container_args, replace_with, run_ffmpeg, stream_map_args, temp_output_path, write_tags,
+};
+use crate::formats::{AudioFormat, AudioQuality};
+use crate::registry::DownloadControl;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoudnessMode {
+    #[default]
+    Off,
+    // Re-encode to the target loudness with ffmpeg's two-pass loudnorm
+    Normalize,
+    // Measure only and write ReplayGain tags, leaving the audio untouched
+    ReplayGain,
+}
+
+fn default_target_lufs() -> f64 {
+    -14.0
+}
+
+fn default_true_peak() -> f64 {
+    -1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoudnessOptions {
+    #[serde(default)]
+    pub mode: LoudnessMode,
+    // Integrated loudness target (EBU R128), used by Normalize
+    #[serde(default = "default_target_lufs")]
+    pub target_lufs: f64,
+    // Maximum true peak in dBTP, used by Normalize
+    #[serde(default = "default_true_peak")]
+    pub true_peak: f64,
+}
+
+impl Default for LoudnessOptions {
+    fn default() -> Self {
+        Self {
+            mode: LoudnessMode::default(),
+            target_lufs: default_target_lufs(),
+            true_peak: default_true_peak(),
+        }
+    }
+}
+
+impl LoudnessOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.mode != LoudnessMode::Off
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        // The ranges ffmpeg's loudnorm filter accepts
+        if !(-70.0..=-5.0).contains(&self.target_lufs) {
+            return Err("Target loudness must be between -70 and -5 LUFS".to_string());
+        }
+
+        if !(-9.0..=0.0).contains(&self.true_peak) {
+            return Err("True peak must be between -9 and 0 dBTP".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Normalize or tag the finished files. `album_source` is the whole unsplit
+// recording when the files are chapter tracks, so they can share an album gain.
+pub async fn apply_loudness(
+    app: &AppHandle,
+    control: &DownloadControl,
+    options: &LoudnessOptions,
+    files: &[PathBuf],
+    album_source: Option<&Path>,
+    format: AudioFormat,
+    quality: Option<&AudioQuality>,
+) -> Result<(), DownloadError> {
+    match options.mode {
+        LoudnessMode::Off => Ok(()),
+        LoudnessMode::Normalize => {
+            for file in files {
+                normalize(app, control, file, options, format, quality).await?;
+            }
+            Ok(())
+        }
+        LoudnessMode::ReplayGain => {
+            let album = match album_source {
+                Some(source) => Some(measure_replaygain(app, control, source).await?),
+                None => None,
+            };
+
+            // A single download is not an album, so it only gets track values
+            for file in files {
+                let track = measure_replaygain(app, control, file).await?;
+                write_replaygain(app, control, file, track, album, format).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+// Values printed by loudnorm's first pass, as strings
+#[derive(Debug, Deserialize)]
+struct LoudnormMeasurement {
+    input_i: String,
+    input_tp: String,
+    input_lra: String,
+    input_thresh: String,
+    target_offset: String,
+}
+
+async fn normalize(
+    app: &AppHandle,
+    control: &DownloadControl,
+    path: &Path,
+    options: &LoudnessOptions,
+    format: AudioFormat,
+    quality: Option<&AudioQuality>,
+) -> Result<(), DownloadError> {
+    let target = format!(
+        "loudnorm=I={}:TP={}:LRA=11",
+        options.target_lufs, options.true_peak
+    );
+
+    let stderr = run_ffmpeg(
+        app,
+        &[
+            "-i".to_string(),
+            path.to_string_lossy().to_string(),
+            "-map".to_string(),
+            "0:a:0".to_string(),
+            "-af".to_string(),
+            format!("{}:print_format=json", target),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ],
+        control,
+    )
+    .await?;
+
+    let measured = parse_loudnorm_json(&stderr).ok_or_else(|| {
+        DownloadError::from_stderr(format!("Could not measure loudness: {}", stderr))
+    })?;
+
+    // Silence measures as -inf and cannot be normalized
+    if !measured
+        .input_i
+        .parse::<f64>()
+        .is_ok_and(|value| value.is_finite())
+    {
+        return Ok(());
+    }
+
+    let filter = format!(
+        "{}:measured_I={}:measured_TP={}:measured_LRA={}:measured_thresh={}:offset={}:linear=true",
+        target,
+        measured.input_i,
+        measured.input_tp,
+        measured.input_lra,
+        measured.input_thresh,
+        measured.target_offset
+    );
+
+    let temp = temp_output_path(path);
+    let mut args = vec!["-i".to_string(), path.to_string_lossy().to_string()];
+    args.extend(stream_map_args(format));
+    args.extend([
+        "-c:v".to_string(),
+        "copy".to_string(),
+        "-map_metadata".to_string(),
+        "0".to_string(),
+        "-af".to_string(),
+        filter,
+        // loudnorm upsamples to 192 kHz internally
+        "-ar".to_string(),
+        "48000".to_string(),
+    ]);
+    args.extend(format.ffmpeg_encoder_args(quality));
+    args.extend(container_args(format));
+    args.push(temp.to_string_lossy().to_string());
+
+    run_ffmpeg(app, &args, control).await?;
+    replace_with(path, &temp)
+}
+
+// loudnorm prints its JSON as the last thing on stderr
+fn parse_loudnorm_json(stderr: &str) -> Option<LoudnormMeasurement> {
+    let start = stderr.rfind('{')?;
+    let end = stderr[start..].find('}')? + start;
+    serde_json::from_str(&stderr[start..=end]).ok()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ReplayGain {
+    // dB relative to the ReplayGain reference level
+    gain: f64,
+    // Linear sample peak, 1.0 being full scale
+    peak: f64,
+}
+
+async fn measure_replaygain(
+    app: &AppHandle,
+    control: &DownloadControl,
+    path: &Path,
+) -> Result<ReplayGain, DownloadError> {
+    let stderr = run_ffmpeg(
+        app,
+        &[
+            "-i".to_string(),
+            path.to_string_lossy().to_string(),
+            "-map".to_string(),
+            "0:a:0".to_string(),
+            "-af".to_string(),
+            "replaygain".to_string(),
+            "-f".to_string(),
+            "null".to_string(),
+            "-".to_string(),
+        ],
+        control,
+    )
+    .await?;
+
+    // "[Parsed_replaygain_0 @ 0x...] track_gain = -7.12 dB" and "track_peak = 0.998"
+    let value = |key: &str| {
+        stderr.lines().rev().find_map(|line| {
+            line.split_once(key)?
+                .1
+                .trim_start_matches([' ', '='])
+                .split_whitespace()
+                .next()?
+                .parse::<f64>()
+                .ok()
+        })
+    };
+
+    match (value("track_gain"), value("track_peak")) {
+        (Some(gain), Some(peak)) => Ok(ReplayGain { gain, peak }),
+        _ => Err(DownloadError::from_stderr(format!(
+            "Could not measure ReplayGain: {}",
+            stderr
+        ))),
+    }
+}
+
+async fn write_replaygain(
+    app: &AppHandle,
+    control: &DownloadControl,
+    path: &Path,
+    track: ReplayGain,
+    album: Option<ReplayGain>,
+    format: AudioFormat,
+) -> Result<(), DownloadError> {
+    let mut tags = vec![
+        ("REPLAYGAIN_TRACK_GAIN", format!("{:.2} dB", track.gain)),
+        ("REPLAYGAIN_TRACK_PEAK", format!("{:.6}", track.peak)),
+    ];
+    if let Some(album) = album {
+        tags.push(("REPLAYGAIN_ALBUM_GAIN", format!("{:.2} dB", album.gain)));
+        tags.push(("REPLAYGAIN_ALBUM_PEAK", format!("{:.6}", album.peak)));
+    }
+
+    write_tags(app, control, path, format, &tags).await
+}
@@ -0,0 +1,573 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn default_languages() -> Vec<String> {
+    vec!["en".to_string()]
+}
+
+fn default_auto_captions() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LyricsOptions {
+    // Synced lyrics converted from the subtitle timing
+    #[serde(default)]
+    pub export_lrc: bool,
+    // Plain transcript without timing
+    #[serde(default)]
+    pub export_txt: bool,
+    // Unsynced lyrics in the ID3 USLT frame (MP3 only)
+    #[serde(default)]
+    pub embed: bool,
+    // yt-dlp subtitle language codes in order of preference, e.g. ["en", "de"]
+    #[serde(default = "default_languages")]
+    pub languages: Vec<String>,
+    // Fall back to YouTube's automatic captions when there are no subtitles
+    #[serde(default = "default_auto_captions")]
+    pub auto_captions: bool,
+}
+
+impl Default for LyricsOptions {
+    fn default() -> Self {
+        Self {
+            export_lrc: false,
+            export_txt: false,
+            embed: false,
+            languages: default_languages(),
+            auto_captions: default_auto_captions(),
+        }
+    }
+}
+
+impl LyricsOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.export_lrc || self.export_txt || self.embed
+    }
+
+    pub fn ytdlp_args(&self) -> Vec<String> {
+        let mut args = vec!["--write-subs".to_string()];
+        if self.auto_captions {
+            args.push("--write-auto-subs".to_string());
+        }
+        args.extend([
+            "--sub-langs".to_string(),
+            self.languages.join(","),
+            "--sub-format".to_string(),
+            "vtt/best".to_string(),
+            "--convert-subs".to_string(),
+            "vtt".to_string(),
+        ]);
+        args
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.is_enabled() && self.languages.iter().all(|l| l.trim().is_empty()) {
+            return Err("Choose at least one subtitle language".to_string());
+        }
+
+        if self.languages.iter().any(|l| l.contains(',')) {
+            return Err("Subtitle languages must be listed one per entry".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// One line of text and when it first appears, in seconds
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    start: f64,
+    text: String,
+}
+
+// The subtitle yt-dlp wrote for a file ("Song.en.vtt"), picked by language
+// preference, with the language it is in
+pub fn find_subtitle(staged_path: &Path, languages: &[String]) -> Option<(PathBuf, String)> {
+    let stem = staged_path.file_stem()?.to_string_lossy().to_string();
+    let folder = staged_path.parent()?;
+
+    let candidates: Vec<(PathBuf, String)> = fs::read_dir(folder)
+        .ok()?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let lang = name
+                .strip_prefix(&stem)?
+                .strip_prefix('.')?
+                .strip_suffix(".vtt")?
+                .to_string();
+            Some((path, lang))
+        })
+        .collect();
+
+    languages
+        .iter()
+        .find_map(|wanted| {
+            candidates
+                .iter()
+                .find(|(_, lang)| lang == wanted || lang.starts_with(&format!("{}-", wanted)))
+        })
+        .or_else(|| candidates.first())
+        .cloned()
+}
+
+// Write the requested lyrics files next to the staged file, returning their paths.
+// `cuts` are the spans of the video, in seconds, that are not in the file.
+pub fn export_lyrics(
+    staged_path: &Path,
+    subtitle: &Path,
+    options: &LyricsOptions,
+    cuts: &[(f64, f64)],
+) -> std::io::Result<Vec<PathBuf>> {
+    let lines = read_lines(subtitle, cuts)?;
+    let mut written = Vec::new();
+
+    if options.export_lrc {
+        let path = staged_path.with_extension("lrc");
+        fs::write(&path, to_lrc(&lines))?;
+        written.push(path);
+    }
+
+    if options.export_txt {
+        let path = staged_path.with_extension("txt");
+        fs::write(&path, to_text(&lines))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+pub fn read_transcript(subtitle: &Path, cuts: &[(f64, f64)]) -> std::io::Result<String> {
+    Ok(to_text(&read_lines(subtitle, cuts)?))
+}
+
+// Subtitles always cover the whole video, so lines in a cut are dropped and
+// the rest are moved earlier by the length of the cuts before them
+fn read_lines(subtitle: &Path, cuts: &[(f64, f64)]) -> std::io::Result<Vec<Line>> {
+    let lines = parse_vtt(&fs::read_to_string(subtitle)?);
+    Ok(apply_cuts(lines, cuts))
+}
+
+fn apply_cuts(lines: Vec<Line>, cuts: &[(f64, f64)]) -> Vec<Line> {
+    let cuts = merge_cuts(cuts);
+
+    lines
+        .into_iter()
+        .filter(|line| {
+            !cuts
+                .iter()
+                .any(|(from, to)| (*from..*to).contains(&line.start))
+        })
+        .map(|line| {
+            let removed: f64 = cuts
+                .iter()
+                .filter(|(from, _)| *from < line.start)
+                .map(|(from, to)| to.min(line.start) - from)
+                .sum();
+            Line {
+                start: line.start - removed,
+                ..line
+            }
+        })
+        .collect()
+}
+
+// Sorted, with overlapping spans joined so no time is counted twice
+fn merge_cuts(cuts: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut sorted: Vec<(f64, f64)> = cuts
+        .iter()
+        .copied()
+        .filter(|(from, to)| to > from)
+        .collect();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (from, to) in sorted {
+        match merged.last_mut() {
+            Some(last) if from <= last.1 => last.1 = last.1.max(to),
+            _ => merged.push((from, to)),
+        }
+    }
+    merged
+}
+
+fn parse_vtt(vtt: &str) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut start = None;
+
+    for raw in vtt.lines() {
+        let raw = raw.trim();
+
+        if let Some((from, _)) = raw.split_once("-->") {
+            start = parse_vtt_time(from.trim());
+            continue;
+        }
+
+        // Blank lines end a cue; anything before the first cue is header
+        let Some(cue_start) = start else {
+            continue;
+        };
+        if raw.is_empty() {
+            start = None;
+            continue;
+        }
+
+        let text = clean_cue_text(raw);
+        // Automatic captions repeat the previous line as each new one rolls in
+        let repeated = lines.iter().rev().take(2).any(|line| line.text == text);
+        if !text.is_empty() && !repeated {
+            lines.push(Line {
+                start: cue_start,
+                text,
+            });
+        }
+    }
+
+    lines
+}
+
+// "01:02:03.456" or "02:03.456"
+fn parse_vtt_time(text: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in text.split(':') {
+        seconds = seconds * 60.0 + part.trim().parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+// Drop inline timing and styling tags and decode the entities VTT allows
+fn clean_cue_text(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut in_tag = false;
+
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => cleaned.push(c),
+            _ => {}
+        }
+    }
+
+    cleaned
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn to_lrc(lines: &[Line]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let centis = (line.start * 100.0).round() as u64;
+            format!(
+                "[{:02}:{:02}.{:02}]{}\n",
+                centis / 6000,
+                centis / 100 % 60,
+                centis % 100,
+                line.text
+            )
+        })
+        .collect()
+}
+
+fn to_text(lines: &[Line]) -> String {
+    lines
+        .iter()
+        .map(|line| format!("{}\n", line.text))
+        .collect()
+}
+
+// Add an unsynced lyrics (USLT) frame to the ID3v2 tag at the start of an MP3,
+// creating the tag if there is none. ffmpeg cannot write this frame itself.
+// Tags we cannot rewrite safely are left alone rather than stacked with a second tag.
+pub fn embed_uslt(path: &Path, lyrics: &str, language: &str) -> std::io::Result<()> {
+    let data = fs::read(path)?;
+
+    let (version, frames, audio) = match parse_id3_header(&data) {
+        Some((version, tag_size)) => {
+            let body = &data[10..10 + tag_size];
+            (version, strip_uslt(body, version), &data[10 + tag_size..])
+        }
+        None if data.starts_with(b"ID3") => {
+            eprintln!(
+                "Not embedding lyrics in {}: its ID3 tag version or flags are not supported",
+                path.display()
+            );
+            return Ok(());
+        }
+        None => (3, Vec::new(), &data[..]),
+    };
+
+    let mut frame_body = vec![1u8]; // UTF-16 with BOM, valid in ID3v2.3 and 2.4
+    frame_body.extend(id3_language(language).as_bytes());
+    frame_body.extend([0xFF, 0xFE, 0x00, 0x00]); // empty description
+    frame_body.extend([0xFF, 0xFE]);
+    frame_body.extend(lyrics.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+
+    let mut tag_body = frames;
+    tag_body.extend(b"USLT");
+    tag_body.extend(encode_size(frame_body.len(), version));
+    tag_body.extend([0, 0]);
+    tag_body.extend(frame_body);
+
+    let mut output = Vec::with_capacity(10 + tag_body.len() + audio.len());
+    output.extend(b"ID3");
+    output.extend([version, 0, 0]);
+    output.extend(encode_syncsafe(tag_body.len()));
+    output.extend(tag_body);
+    output.extend(audio);
+
+    fs::write(path, output)
+}
+
+// Version and size of a plain ID3v2.3/2.4 tag; others are treated as absent
+fn parse_id3_header(data: &[u8]) -> Option<(u8, usize)> {
+    if data.len() < 10 || &data[..3] != b"ID3" || !matches!(data[3], 3 | 4) {
+        return None;
+    }
+    // Unsynchronisation, extended headers and footers are not produced by ffmpeg
+    if data[5] & 0xD0 != 0 {
+        return None;
+    }
+
+    let size = decode_syncsafe(&data[6..10]);
+    (data.len() >= 10 + size).then_some((data[3], size))
+}
+
+// Existing frames without padding or an older USLT frame
+fn strip_uslt(body: &[u8], version: u8) -> Vec<u8> {
+    let mut frames = Vec::new();
+    let mut pos = 0;
+
+    while pos + 10 <= body.len() && body[pos] != 0 {
+        let size = match version {
+            4 => decode_syncsafe(&body[pos + 4..pos + 8]),
+            _ => u32::from_be_bytes([body[pos + 4], body[pos + 5], body[pos + 6], body[pos + 7]])
+                as usize,
+        };
+        let end = (pos + 10 + size).min(body.len());
+
+        if &body[pos..pos + 4] != b"USLT" {
+            frames.extend(&body[pos..end]);
+        }
+        pos = end;
+    }
+
+    frames
+}
+
+fn encode_size(size: usize, version: u8) -> [u8; 4] {
+    match version {
+        4 => encode_syncsafe(size),
+        _ => (size as u32).to_be_bytes(),
+    }
+}
+
+fn encode_syncsafe(size: usize) -> [u8; 4] {
+    let size = size as u32;
+    [
+        ((size >> 21) & 0x7F) as u8,
+        ((size >> 14) & 0x7F) as u8,
+        ((size >> 7) & 0x7F) as u8,
+        (size & 0x7F) as u8,
+    ]
+}
+
+fn decode_syncsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take(4)
+        .fold(0usize, |size, byte| (size << 7) | (*byte & 0x7F) as usize)
+}
+
+// ID3 wants ISO 639-2 codes; yt-dlp uses two-letter codes such as "en" or "en-US"
+fn id3_language(lang: &str) -> &'static str {
+    const CODES: [(&str, &str); 14] = [
+        ("en", "eng"),
+        ("de", "deu"),
+        ("fr", "fra"),
+        ("es", "spa"),
+        ("it", "ita"),
+        ("pt", "por"),
+        ("nl", "nld"),
+        ("ru", "rus"),
+        ("pl", "pol"),
+        ("tr", "tur"),
+        ("ja", "jpn"),
+        ("ko", "kor"),
+        ("zh", "zho"),
+        ("ar", "ara"),
+    ];
+
+    let base = lang.split('-').next().unwrap_or(lang);
+    CODES
+        .iter()
+        .find(|(short, _)| base.eq_ignore_ascii_case(short))
+        .map(|(_, code)| *code)
+        .unwrap_or("und")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AUDIO: &[u8] = b"\xFF\xFB\x90\x00audio frames";
+
+    fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.mp3", name, std::process::id()));
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn frame(id: &[u8], body: &[u8], version: u8) -> Vec<u8> {
+        let mut frame = id.to_vec();
+        frame.extend(encode_size(body.len(), version));
+        frame.extend([0, 0]);
+        frame.extend(body);
+        frame
+    }
+
+    fn tag(version: u8, frames: &[Vec<u8>]) -> Vec<u8> {
+        let body = frames.concat();
+        let mut tag = b"ID3".to_vec();
+        tag.extend([version, 0, 0]);
+        tag.extend(encode_syncsafe(body.len()));
+        tag.extend(body);
+        tag
+    }
+
+    // Frame id and body
+    type Frame = (String, Vec<u8>);
+
+    // Version and frames of the tag, and the bytes after it
+    fn read_tag(data: &[u8]) -> (u8, Vec<Frame>, Vec<u8>) {
+        let (version, size) = parse_id3_header(data).expect("no readable ID3 tag");
+        let body = &data[10..10 + size];
+        let mut frames = Vec::new();
+        let mut pos = 0;
+        while pos + 10 <= body.len() && body[pos] != 0 {
+            let size = match version {
+                4 => decode_syncsafe(&body[pos + 4..pos + 8]),
+                _ => u32::from_be_bytes(body[pos + 4..pos + 8].try_into().unwrap()) as usize,
+            };
+            let id = String::from_utf8_lossy(&body[pos..pos + 4]).to_string();
+            frames.push((id, body[pos + 10..pos + 10 + size].to_vec()));
+            pos += 10 + size;
+        }
+        assert!(!data[10 + size..].starts_with(b"ID3"), "tags are stacked");
+        (version, frames, data[10 + size..].to_vec())
+    }
+
+    fn uslt_text(body: &[u8]) -> String {
+        // Encoding, language, empty description with BOM and terminator, then the BOM
+        let units: Vec<u16> = body[10..]
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).unwrap()
+    }
+
+    fn line(start: f64, text: &str) -> Line {
+        Line {
+            start,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn cuts_drop_lines_and_shift_the_rest() {
+        let lines = vec![
+            line(5.0, "before the range"),
+            line(12.0, "first"),
+            line(25.0, "sponsor"),
+            line(40.0, "after the sponsor"),
+            line(95.0, "after the range"),
+        ];
+        // Range 10-90 with a sponsor at 20-30, which overlaps a second segment
+        let cuts = [
+            (0.0, 10.0),
+            (90.0, f64::INFINITY),
+            (20.0, 30.0),
+            (22.0, 28.0),
+        ];
+
+        assert_eq!(
+            apply_cuts(lines, &cuts),
+            vec![line(2.0, "first"), line(20.0, "after the sponsor")]
+        );
+    }
+
+    #[test]
+    fn creates_a_tag_when_there_is_none() {
+        let path = temp_file("uslt-none", AUDIO);
+        embed_uslt(&path, "Hello\nWorld\n", "en").unwrap();
+
+        let (version, frames, audio) = read_tag(&fs::read(&path).unwrap());
+        assert_eq!(version, 3);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].0, "USLT");
+        assert_eq!(&frames[0].1[1..4], b"eng");
+        assert_eq!(uslt_text(&frames[0].1), "Hello\nWorld\n");
+        assert_eq!(audio, AUDIO);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn keeps_existing_frames_in_v23_and_v24_tags() {
+        for version in [3, 4] {
+            let title = frame(b"TIT2", b"\x00Song", version);
+            // Padding follows the frames inside the tag
+            let mut data = tag(version, &[title, vec![0; 16]]);
+            data.extend(AUDIO);
+            let path = temp_file(&format!("uslt-v2{}", version), &data);
+
+            embed_uslt(&path, "Lyrics", "de").unwrap();
+
+            let (written, frames, audio) = read_tag(&fs::read(&path).unwrap());
+            assert_eq!(written, version);
+            assert_eq!(frames[0], ("TIT2".to_string(), b"\x00Song".to_vec()));
+            assert_eq!(frames[1].0, "USLT");
+            assert_eq!(&frames[1].1[1..4], b"deu");
+            assert_eq!(uslt_text(&frames[1].1), "Lyrics");
+            assert_eq!(audio, AUDIO);
+            let _ = fs::remove_file(&path);
+        }
+    }
+
+    #[test]
+    fn replaces_an_existing_uslt_frame() {
+        let path = temp_file("uslt-replace", AUDIO);
+        embed_uslt(&path, "First", "en").unwrap();
+        embed_uslt(&path, "Second", "en").unwrap();
+
+        let (_, frames, audio) = read_tag(&fs::read(&path).unwrap());
+        let lyrics: Vec<_> = frames.iter().filter(|(id, _)| id == "USLT").collect();
+        assert_eq!(lyrics.len(), 1);
+        assert_eq!(uslt_text(&lyrics[0].1), "Second");
+        assert_eq!(audio, AUDIO);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn leaves_unsupported_tags_alone() {
+        let mut v22 = b"ID3\x02\x00\x00\x00\x00\x00\x06TT2\x00\x00\x00".to_vec();
+        v22.extend(AUDIO);
+        let mut unsynchronised = tag(3, &[frame(b"TIT2", b"\x00Song", 3)]);
+        unsynchronised[5] = 0x80;
+        unsynchronised.extend(AUDIO);
+
+        for (name, data) in [("uslt-v22", v22), ("uslt-unsync", unsynchronised)] {
+            let path = temp_file(name, &data);
+            embed_uslt(&path, "Lyrics", "en").unwrap();
+            assert_eq!(fs::read(&path).unwrap(), data);
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
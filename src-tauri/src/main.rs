@@ -1,23 +1,54 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod archive;
 mod binaries;
 mod commands;
+mod cover;
 mod downloader;
+mod error;
+mod ffmpeg;
+mod filename;
+mod formats;
+mod history;
+mod loudness;
+mod lyrics;
 mod playlist;
+mod progress;
+mod queue;
+mod registry;
 mod settings;
+mod sponsorblock;
+mod tagging;
+mod template;
 
+use archive::DownloadArchive;
 use commands::*;
+use history::HistoryDb;
+use queue::QueueStore;
+use registry::DownloadRegistry;
 use settings::Settings;
 use std::sync::Mutex;
+use tauri::Manager;
 
 pub struct AppState {
     pub settings: Mutex<Settings>,
+    pub downloads: DownloadRegistry,
+    // Running playlist fetches, keyed by request id
+    pub fetches: DownloadRegistry,
+    pub queue: QueueStore,
+    pub history: HistoryDb,
+    pub archive: DownloadArchive,
 }
 
 fn main() {
     let settings = settings::load_settings().unwrap_or_default();
     let app_state = AppState {
         settings: Mutex::new(settings),
+        downloads: DownloadRegistry::default(),
+        fetches: DownloadRegistry::default(),
+        queue: QueueStore::default(),
+        history: HistoryDb::default(),
+        archive: DownloadArchive::default(),
     };
 
     tauri::Builder::default()
@@ -30,13 +61,40 @@ fn main() {
             set_download_folder,
             select_folder,
             fetch_playlist,
+            cancel_fetch,
+            preview_output_path,
             start_downloads,
             stop_downloads,
+            cancel_download,
+            cancel_all,
+            pause_download,
+            resume_download,
+            get_unfinished_downloads,
+            resume_unfinished_downloads,
+            discard_unfinished_downloads,
+            list_history,
+            search_history,
+            clear_history,
+            clear_download_archive,
             open_file,
             open_folder
         ])
         .setup(|app| {
             let app_handle = app.handle().clone();
+
+            // Restore jobs left unfinished by the previous run
+            if let Err(e) = app.state::<AppState>().queue.load(&app_handle) {
+                eprintln!("Failed to load download queue: {}", e);
+            }
+
+            if let Err(e) = app.state::<AppState>().history.open(&app_handle) {
+                eprintln!("Failed to open download history: {}", e);
+            }
+
+            if let Err(e) = app.state::<AppState>().archive.load(&app_handle) {
+                eprintln!("Failed to load download archive: {}", e);
+            }
+
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = settings::ensure_download_folder(&app_handle).await {
                     eprintln!("Failed to ensure download folder: {}", e);
@@ -1,8 +1,13 @@
 use crate::binaries::BinaryManager;
+use crate::error::{last_error_line, ErrorCode, ErrorInfo};
+use crate::registry::DownloadControl;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
-use std::str;
-use tauri::AppHandle;
+use std::collections::HashSet;
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{ChildStdout, Command as TokioCommand};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
@@ -11,31 +16,236 @@ pub struct Item {
     pub duration: Option<String>,
     pub thumbnail: Option<String>,
     pub url: String,
+    #[serde(default)]
+    pub uploader: Option<String>,
+    // Unset when the video was listed from a playlist, whose entries carry no
+    // chapters; empty when the video has none
+    #[serde(default)]
+    pub chapters: Option<Vec<Chapter>>,
+    // 1-based position in the playlist it was listed from
+    #[serde(default)]
+    pub playlist_index: Option<u32>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    // Offsets into the video, in seconds
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistKind {
+    Video,
+    Playlist,
+    // Radio-style mixes ("RD..." lists) that YouTube keeps extending
+    Mix,
+    // A channel's uploads
+    Channel,
+}
+
+impl PlaylistKind {
+    fn from_url(url: &str) -> Self {
+        if is_mix_url(url) {
+            PlaylistKind::Mix
+        } else if is_channel_url(url) {
+            PlaylistKind::Channel
+        } else if url.contains("playlist") || url.contains("list=") {
+            PlaylistKind::Playlist
+        } else {
+            PlaylistKind::Video
+        }
+    }
+}
+
+// What a fetch found. For a single video, the playlist fields describe the video
+// itself. The range given to the fetch decides which part `items` covers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistInfo {
+    pub id: String,
+    pub title: String,
+    pub uploader: Option<String>,
+    // The first item's thumbnail, as flat listings carry none for the playlist
+    pub thumbnail: Option<String>,
+    // Size of the whole playlist as reported by YouTube; unknown for mixes
+    pub item_count: Option<u32>,
+    pub kind: PlaylistKind,
+    pub items: Vec<Item>,
+    // More items follow the last one listed
+    pub truncated: bool,
+}
+
+// Sent as "playlist-item" for each item as soon as yt-dlp lists it
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistItemEvent {
+    // Given by the caller of fetch_playlist
+    pub request_id: String,
+    // Position among the items listed so far
+    pub index: usize,
+    pub item: Item,
+}
+
+// Sent as "playlist-complete" once a fetch has listed everything
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistCompleteEvent {
+    pub request_id: String,
+    // Items listed by this fetch
+    pub loaded: usize,
+    pub item_count: Option<u32>,
+    pub truncated: bool,
+}
+
+// Which part of a playlist to list. Positions are 1-based and inclusive.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ItemRange {
+    pub start: Option<u32>,
+    pub end: Option<u32>,
+    // Most items to list from `start`
+    pub limit: Option<u32>,
+}
+
+impl ItemRange {
+    fn validate(&self) -> Result<(), PlaylistError> {
+        if self.start == Some(0) || self.limit == Some(0) {
+            return Err(PlaylistError::InvalidRange(
+                "Positions and limits start at 1".to_string(),
+            ));
+        }
+
+        match self.end {
+            Some(end) if end < self.first() => Err(PlaylistError::InvalidRange(format!(
+                "End {} is before start {}",
+                end,
+                self.first()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn first(&self) -> u32 {
+        self.start.unwrap_or(1)
+    }
+
+    // How many items to list at most, if bounded
+    fn count(&self, cap: Option<u32>) -> Option<u32> {
+        // Subtract first, as `end + 1` overflows for an end of u32::MAX
+        let through_end = self.end.map(|end| (end - self.first()).saturating_add(1));
+        [through_end, self.limit, cap].into_iter().flatten().min()
+    }
+}
+
+fn default_mix_item_cap() -> u32 {
+    100
+}
+
+fn default_fetch_timeout_secs() -> u64 {
+    300
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaylistOptions {
+    // Mixes never end, so listing one stops after this many items
+    #[serde(default = "default_mix_item_cap")]
+    pub mix_item_cap: u32,
+    // A fetch still running after this long is stopped
+    #[serde(default = "default_fetch_timeout_secs")]
+    pub fetch_timeout_secs: u64,
+}
+
+impl Default for PlaylistOptions {
+    fn default() -> Self {
+        Self {
+            mix_item_cap: default_mix_item_cap(),
+            fetch_timeout_secs: default_fetch_timeout_secs(),
+        }
+    }
+}
+
+impl PlaylistOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=5000).contains(&self.mix_item_cap) {
+            return Err("Mix item limit must be between 1 and 5000".to_string());
+        }
+
+        if !(10..=3600).contains(&self.fetch_timeout_secs) {
+            return Err("Fetch timeout must be between 10 and 3600 seconds".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum PlaylistError {
     InvalidUrl,
+    InvalidRange(String),
     YtDlpNotFound,
-    FetchFailed(String),
+    // yt-dlp exited with an error, classified from its stderr
+    FetchFailed { code: ErrorCode, stderr: String },
     ParseError(String),
     BinaryError(String),
+    Cancelled,
+    // Seconds the fetch was allowed to run
+    TimedOut(u64),
 }
 
 impl std::fmt::Display for PlaylistError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             PlaylistError::InvalidUrl => write!(f, "Invalid YouTube URL"),
+            PlaylistError::InvalidRange(msg) => write!(f, "Invalid item range: {}", msg),
             PlaylistError::YtDlpNotFound => write!(f, "yt-dlp not found. Please install yt-dlp"),
-            PlaylistError::FetchFailed(msg) => write!(f, "Failed to fetch playlist: {}", msg),
+            PlaylistError::FetchFailed { stderr, .. } => {
+                write!(f, "Failed to fetch playlist: {}", last_error_line(stderr))
+            }
             PlaylistError::ParseError(msg) => write!(f, "Failed to parse playlist data: {}", msg),
             PlaylistError::BinaryError(msg) => write!(f, "Binary error: {}", msg),
+            PlaylistError::Cancelled => write!(f, "Playlist fetch cancelled"),
+            PlaylistError::TimedOut(secs) => {
+                write!(f, "Playlist fetch timed out after {} seconds", secs)
+            }
         }
     }
 }
 
 impl std::error::Error for PlaylistError {}
 
+impl PlaylistError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PlaylistError::InvalidUrl => ErrorCode::InvalidUrl,
+            PlaylistError::InvalidRange(_) => ErrorCode::InvalidRange,
+            PlaylistError::YtDlpNotFound | PlaylistError::BinaryError(_) => {
+                ErrorCode::YtDlpNotFound
+            }
+            PlaylistError::FetchFailed { code, .. } => *code,
+            PlaylistError::ParseError(_) => ErrorCode::ParseError,
+            PlaylistError::Cancelled => ErrorCode::FetchCancelled,
+            PlaylistError::TimedOut(_) => ErrorCode::FetchTimedOut,
+        }
+    }
+
+    pub fn info(&self) -> ErrorInfo {
+        let detail = match self {
+            PlaylistError::FetchFailed { stderr, .. } => Some(last_error_line(stderr)),
+            PlaylistError::ParseError(msg)
+            | PlaylistError::BinaryError(msg)
+            | PlaylistError::InvalidRange(msg) => Some(msg.clone()),
+            _ => None,
+        };
+        ErrorInfo::new(self.code(), detail)
+    }
+}
+
+// Sent to the frontend as a structured ErrorInfo
+impl Serialize for PlaylistError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.info().serialize(serializer)
+    }
+}
+
 pub fn validate_youtube_url(url: &str) -> bool {
     let youtube_patterns = [
         "youtube.com/watch",
@@ -43,16 +253,74 @@ pub fn validate_youtube_url(url: &str) -> bool {
         "youtu.be/",
         "youtube.com/mix",
         "music.youtube.com",
+        "youtube.com/@",
+        "youtube.com/channel/",
+        "youtube.com/c/",
+        "youtube.com/user/",
     ];
 
     youtube_patterns.iter().any(|pattern| url.contains(pattern))
 }
 
-pub async fn fetch_playlist_items(app: &AppHandle, url: &str) -> Result<Vec<Item>, PlaylistError> {
+fn is_mix_url(url: &str) -> bool {
+    url.contains("youtube.com/mix") || url.contains("list=RD") || url.contains("start_radio=")
+}
+
+fn is_channel_url(url: &str) -> bool {
+    [
+        "youtube.com/@",
+        "youtube.com/channel/",
+        "youtube.com/c/",
+        "youtube.com/user/",
+    ]
+    .iter()
+    .any(|pattern| url.contains(pattern))
+}
+
+// A channel's home page lists its tabs rather than its videos, so point it at
+// the videos tab, replacing any other tab and keeping the query
+fn channel_videos_url(url: &str) -> String {
+    let (base, query) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let path_start = base
+        .find("youtube.com/")
+        .map_or(base.len(), |i| i + "youtube.com/".len());
+    let (host, path) = base.split_at(path_start);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    // "@handle" names the channel alone; "channel/", "c/" and "user/" need two segments
+    let root = if segments.first().is_some_and(|s| s.starts_with('@')) {
+        1
+    } else {
+        2
+    };
+    let tab = match segments.get(root) {
+        Some(&tab) if ["videos", "shorts", "streams"].contains(&tab) => tab,
+        _ => "videos",
+    };
+
+    format!(
+        "{}{}/{}{}",
+        host,
+        segments[..root.min(segments.len())].join("/"),
+        tab,
+        query
+    )
+}
+
+// `control` cancels the fetch, killing yt-dlp
+pub async fn fetch_playlist_items(
+    app: &AppHandle,
+    request_id: &str,
+    url: &str,
+    range: &ItemRange,
+    options: &PlaylistOptions,
+    control: &DownloadControl,
+) -> Result<PlaylistInfo, PlaylistError> {
     // Validate URL
     if !validate_youtube_url(url) {
         return Err(PlaylistError::InvalidUrl);
     }
+    range.validate()?;
 
     // Get bundled yt-dlp binary path
     let yt_dlp_path = BinaryManager::get_binary_path(app, "yt-dlp")
@@ -63,66 +331,241 @@ pub async fn fetch_playlist_items(app: &AppHandle, url: &str) -> Result<Vec<Item
         return Err(PlaylistError::YtDlpNotFound);
     }
 
-    // Determine if it's a playlist/mix or single video
-    let is_playlist = url.contains("playlist") || url.contains("mix") || url.contains("&list=");
+    let kind = PlaylistKind::from_url(url);
+
+    let mut args = vec!["--dump-json".to_string(), "--no-warnings".to_string()];
+    let mut max_items = None;
+
+    if kind != PlaylistKind::Video {
+        // Only mixes are capped without being asked, as they never end
+        let cap = (kind == PlaylistKind::Mix).then_some(options.mix_item_cap);
+        max_items = range.count(cap);
+
+        // List one item past the end to find out whether anything was left out
+        let last = max_items
+            .map(|count| range.first().saturating_add(count).to_string())
+            .unwrap_or_default();
+        args.extend([
+            "--flat-playlist".to_string(),
+            "--playlist-items".to_string(),
+            format!("{}:{}", range.first(), last),
+        ]);
+    }
 
-    let mut args = vec!["--dump-json", "--no-warnings"];
+    if kind == PlaylistKind::Channel {
+        args.push(channel_videos_url(url));
+    } else {
+        args.push(url.to_string());
+    }
 
-    // For playlists, limit to avoid infinite mixes
-    if is_playlist {
-        args.extend_from_slice(&["--flat-playlist", "--playlist-end", "100"]);
+    let mut child = TokioCommand::new(&yt_dlp_path)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| PlaylistError::BinaryError(format!("Command execution failed: {}", e)))?;
+
+    // Drain stderr while listing so a full pipe cannot stall yt-dlp
+    let stderr_task = child.stderr.take().map(|mut stderr| {
+        tokio::spawn(async move {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output).await;
+            output
+        })
+    });
+
+    // Listing stops early when the fetch is cancelled or runs too long
+    let stdout = child.stdout.take();
+    let listing = Listing::new(kind, max_items);
+    let listed = tokio::select! {
+        listed = read_listing(app, request_id, stdout, listing) => listed,
+        _ = tokio::time::sleep(Duration::from_secs(options.fetch_timeout_secs)) => {
+            Err(PlaylistError::TimedOut(options.fetch_timeout_secs))
+        }
+        _ = wait_for_cancel(control) => Err(PlaylistError::Cancelled),
+    };
+
+    let listing = match listed {
+        Ok(listing) => listing,
+        Err(e) => {
+            let _ = child.kill().await;
+            return Err(e);
+        }
+    };
+
+    // Everything needed has been read; the rest of the playlist is not wanted
+    if listing.truncated {
+        let _ = child.kill().await;
     }
 
-    args.push(url);
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| PlaylistError::BinaryError(format!("Command execution failed: {}", e)))?;
 
-    // Execute yt-dlp command using bundled binary
-    let output = Command::new(&yt_dlp_path)
-        .args(&args)
-        .output()
-        .map_err(|e| PlaylistError::FetchFailed(format!("Command execution failed: {}", e)))?;
+    if !status.success() && !listing.truncated {
+        let error_msg = match stderr_task {
+            Some(task) => task.await.unwrap_or_default(),
+            None => String::new(),
+        };
+        return Err(PlaylistError::FetchFailed {
+            code: ErrorCode::from_ytdlp_stderr(&error_msg),
+            stderr: error_msg,
+        });
+    }
 
-    if !output.status.success() {
-        let error_msg = str::from_utf8(&output.stderr)
-            .unwrap_or("Unknown error")
-            .to_string();
-        return Err(PlaylistError::FetchFailed(error_msg));
+    let playlist = listing.finish()?;
+    let _ = app.emit(
+        "playlist-complete",
+        PlaylistCompleteEvent {
+            request_id: request_id.to_string(),
+            loaded: playlist.items.len(),
+            item_count: playlist.item_count,
+            truncated: playlist.truncated,
+        },
+    );
+
+    Ok(playlist)
+}
+
+// Collect items from yt-dlp's JSON lines as they arrive; it prints one per item
+// as it goes, so the UI can fill in early
+async fn read_listing(
+    app: &AppHandle,
+    request_id: &str,
+    stdout: Option<ChildStdout>,
+    mut listing: Listing,
+) -> Result<Listing, PlaylistError> {
+    let Some(stdout) = stdout else {
+        return Ok(listing);
+    };
+
+    let mut lines = BufReader::new(stdout).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| PlaylistError::ParseError(format!("Invalid output: {}", e)))?
+    {
+        if let Some(item) = listing.push_line(&line)?.cloned() {
+            let _ = app.emit(
+                "playlist-item",
+                PlaylistItemEvent {
+                    request_id: request_id.to_string(),
+                    index: listing.items.len() - 1,
+                    item,
+                },
+            );
+        }
+
+        if listing.truncated {
+            break;
+        }
     }
 
-    let stdout = str::from_utf8(&output.stdout)
-        .map_err(|e| PlaylistError::ParseError(format!("Invalid UTF-8: {}", e)))?;
+    Ok(listing)
+}
+
+async fn wait_for_cancel(control: &DownloadControl) {
+    while !control.is_cancelled() {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
 
-    parse_ytdlp_output(stdout, is_playlist)
+// Items collected from yt-dlp's output, one JSON line at a time
+struct Listing {
+    kind: PlaylistKind,
+    max_items: Option<u32>,
+    items: Vec<Item>,
+    seen_ids: HashSet<String>,
+    entries: u32,
+    // Playlist fields, taken from the first entry
+    header: Option<serde_json::Value>,
+    truncated: bool,
 }
 
-fn parse_ytdlp_output(output: &str, is_playlist: bool) -> Result<Vec<Item>, PlaylistError> {
-    let mut items = Vec::new();
-    let mut seen_ids = std::collections::HashSet::new();
+impl Listing {
+    fn new(kind: PlaylistKind, max_items: Option<u32>) -> Self {
+        Self {
+            kind,
+            max_items,
+            items: Vec::new(),
+            seen_ids: HashSet::new(),
+            entries: 0,
+            header: None,
+            truncated: false,
+        }
+    }
+
+    // Returns the item the line added, if it was a new one
+    fn push_line(&mut self, line: &str) -> Result<Option<&Item>, PlaylistError> {
+        if line.trim().is_empty() || self.truncated {
+            return Ok(None);
+        }
 
-    for line in output.lines() {
-        if line.trim().is_empty() {
-            continue;
+        self.entries += 1;
+        if self.max_items.is_some_and(|max| self.entries > max) {
+            self.truncated = true;
+            return Ok(None);
         }
 
         let json_value: serde_json::Value = serde_json::from_str(line)
             .map_err(|e| PlaylistError::ParseError(format!("JSON parse error: {}", e)))?;
 
-        let item = if is_playlist {
-            parse_playlist_item(&json_value)?
-        } else {
+        let item = if self.kind == PlaylistKind::Video {
             parse_single_video(&json_value)?
+        } else {
+            parse_playlist_item(&json_value)?
         };
 
+        // Every entry of a flat playlist carries the playlist's own fields
+        if self.header.is_none() {
+            self.header = Some(json_value);
+        }
+
         // Skip duplicates based on ID
-        if seen_ids.insert(item.id.clone()) {
-            items.push(item);
+        if !self.seen_ids.insert(item.id.clone()) {
+            return Ok(None);
         }
-    }
 
-    if items.is_empty() {
-        return Err(PlaylistError::ParseError("No items found".to_string()));
+        self.items.push(item);
+        Ok(self.items.last())
     }
 
-    Ok(items)
+    fn finish(self) -> Result<PlaylistInfo, PlaylistError> {
+        let Some(first) = self.items.first() else {
+            return Err(PlaylistError::ParseError("No items found".to_string()));
+        };
+        let header = self.header.unwrap_or_default();
+        let field = |key: &str| header[key].as_str().map(|s| s.to_string());
+
+        let (id, title, uploader, item_count) = if self.kind == PlaylistKind::Video {
+            (
+                first.id.clone(),
+                first.title.clone(),
+                first.uploader.clone(),
+                Some(1),
+            )
+        } else {
+            (
+                field("playlist_id").unwrap_or_default(),
+                field("playlist_title").unwrap_or_else(|| "Unknown Playlist".to_string()),
+                field("playlist_uploader").or_else(|| field("playlist_channel")),
+                header["playlist_count"].as_u64().map(|n| n as u32),
+            )
+        };
+
+        Ok(PlaylistInfo {
+            id,
+            title,
+            uploader,
+            thumbnail: first.thumbnail.clone(),
+            item_count,
+            kind: self.kind,
+            items: self.items,
+            truncated: self.truncated,
+        })
+    }
 }
 
 fn parse_playlist_item(json: &serde_json::Value) -> Result<Item, PlaylistError> {
@@ -149,12 +592,23 @@ fn parse_playlist_item(json: &serde_json::Value) -> Result<Item, PlaylistError>
         format!("https://www.youtube.com/watch?v={}", id)
     };
 
+    let uploader = json["uploader"]
+        .as_str()
+        .or_else(|| json["channel"].as_str())
+        .map(|s| s.to_string());
+
+    let chapters = json["chapters"].is_array().then(|| parse_chapters(json));
+    let playlist_index = json["playlist_index"].as_u64().map(|n| n as u32);
+
     Ok(Item {
         id,
         title,
         duration,
         thumbnail,
         url,
+        uploader,
+        chapters,
+        playlist_index,
     })
 }
 
@@ -189,15 +643,87 @@ fn parse_single_video(json: &serde_json::Value) -> Result<Item, PlaylistError> {
         .unwrap_or(&format!("https://www.youtube.com/watch?v={}", id))
         .to_string();
 
+    let uploader = json["uploader"]
+        .as_str()
+        .or_else(|| json["channel"].as_str())
+        .map(|s| s.to_string());
+
+    // yt-dlp leaves "chapters" null for a video without any
+    let chapters = Some(parse_chapters(json));
+
     Ok(Item {
         id,
         title,
         duration,
         thumbnail,
         url,
+        uploader,
+        chapters,
+        playlist_index: None,
     })
 }
 
+fn parse_chapters(json: &serde_json::Value) -> Vec<Chapter> {
+    json["chapters"]
+        .as_array()
+        .map(|chapters| {
+            chapters
+                .iter()
+                .enumerate()
+                .filter_map(|(i, chapter)| {
+                    Some(Chapter {
+                        title: chapter["title"]
+                            .as_str()
+                            .map(|s| s.to_string())
+                            .unwrap_or_else(|| format!("Chapter {}", i + 1)),
+                        start_time: chapter["start_time"].as_f64()?,
+                        end_time: chapter["end_time"].as_f64()?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Parse "ss", "mm:ss" or "hh:mm:ss" (seconds may have a fraction) into seconds;
+// the inverse of format_duration
+pub fn parse_timestamp(text: &str) -> Option<f64> {
+    let parts: Vec<&str> = text.trim().split(':').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+
+    let (seconds, whole) = parts.split_last()?;
+    // Plain digits only, so "1e3", "inf" and "+5" are rejected
+    let (integer, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+    if !is_digits(integer) || !is_digits(fraction) {
+        return None;
+    }
+    let seconds: f64 = seconds.parse().ok()?;
+    if !whole.is_empty() && seconds >= 60.0 {
+        return None;
+    }
+
+    let mut total = 0u64;
+    for (i, part) in whole.iter().enumerate() {
+        if !is_digits(part) {
+            return None;
+        }
+        let value: u64 = part.parse().ok()?;
+        // Minutes are limited to 59 only when hours are given
+        if i > 0 && value >= 60 {
+            return None;
+        }
+        total = total.checked_mul(60)?.checked_add(value)?;
+    }
+
+    Some(total as f64 * 60.0 + seconds)
+}
+
+fn is_digits(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+}
+
 fn format_duration(seconds: u64) -> String {
     let hours = seconds / 3600;
     let minutes = (seconds % 3600) / 60;
@@ -209,3 +735,98 @@ fn format_duration(seconds: u64) -> String {
         format!("{}:{:02}", minutes, secs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: Option<u32>, end: Option<u32>, limit: Option<u32>) -> ItemRange {
+        ItemRange { start, end, limit }
+    }
+
+    #[test]
+    fn range_count_takes_the_smallest_bound() {
+        assert_eq!(ItemRange::default().count(None), None);
+        assert_eq!(range(Some(3), Some(7), None).count(None), Some(5));
+        assert_eq!(range(Some(3), Some(7), Some(2)).count(None), Some(2));
+        assert_eq!(range(None, Some(500), None).count(Some(100)), Some(100));
+    }
+
+    #[test]
+    fn range_count_does_not_overflow_at_the_top() {
+        assert_eq!(
+            range(None, Some(u32::MAX), None).count(None),
+            Some(u32::MAX)
+        );
+        assert_eq!(
+            range(Some(u32::MAX), Some(u32::MAX), None).count(None),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn chapters_are_unknown_only_for_listed_entries() {
+        let listed = serde_json::json!({ "id": "abc", "title": "Song" });
+        assert!(parse_playlist_item(&listed).unwrap().chapters.is_none());
+
+        let video = serde_json::json!({ "id": "abc", "title": "Song", "chapters": null });
+        assert_eq!(
+            parse_single_video(&video)
+                .unwrap()
+                .chapters
+                .map(|c| c.len()),
+            Some(0)
+        );
+
+        let video = serde_json::json!({
+            "id": "abc",
+            "title": "Song",
+            "chapters": [{ "title": "Intro", "start_time": 0.0, "end_time": 12.5 }],
+        });
+        assert_eq!(
+            parse_single_video(&video)
+                .unwrap()
+                .chapters
+                .map(|c| c.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn channel_urls_point_at_the_videos_tab() {
+        let cases = [
+            (
+                "https://www.youtube.com/@x",
+                "https://www.youtube.com/@x/videos",
+            ),
+            (
+                "https://www.youtube.com/@x/",
+                "https://www.youtube.com/@x/videos",
+            ),
+            (
+                "https://www.youtube.com/@x?si=abc",
+                "https://www.youtube.com/@x/videos?si=abc",
+            ),
+            (
+                "https://www.youtube.com/@x/featured",
+                "https://www.youtube.com/@x/videos",
+            ),
+            (
+                "https://www.youtube.com/@x/streams?si=abc",
+                "https://www.youtube.com/@x/streams?si=abc",
+            ),
+            (
+                "https://www.youtube.com/channel/UC123/playlists",
+                "https://www.youtube.com/channel/UC123/videos",
+            ),
+            (
+                "https://www.youtube.com/user/name/shorts",
+                "https://www.youtube.com/user/name/shorts",
+            ),
+        ];
+
+        for (url, expected) in cases {
+            assert_eq!(channel_videos_url(url), expected, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use crate::downloader::{DownloadProgress, DownloadStatus};
+use crate::sponsorblock::SponsorSegment;
+use crate::tagging::VideoMetadata;
+use serde::Deserialize;
+
+// Prefixes marking the machine-readable lines requested by progress_template_args
+const DOWNLOAD_PREFIX: &str = "[yt-progress] ";
+const POSTPROCESS_PREFIX: &str = "[yt-postprocess] ";
+const OUTPUT_PREFIX: &str = "[yt-output] ";
+const SPONSORBLOCK_PREFIX: &str = "[yt-sponsorblock] ";
+const METADATA_PREFIX: &str = "[yt-metadata] ";
+// Status lines of yt-dlp's chapter splitter, shown thanks to --no-quiet
+const SPLIT_CHAPTERS_PREFIX: &str = "[SplitChapters] ";
+
+// Arguments making yt-dlp print one JSON object per progress update,
+// plus the final output path
+pub fn progress_template_args() -> Vec<String> {
+    vec![
+        // Any --print switches yt-dlp to quiet mode, which would also hide its
+        // progress and status lines. --no-quiet has to undo that explicitly.
+        "--no-quiet".to_string(),
+        "--progress-template".to_string(),
+        format!("download:{}%(progress)j", DOWNLOAD_PREFIX),
+        "--progress-template".to_string(),
+        format!("postprocess:{}%(progress)j", POSTPROCESS_PREFIX),
+        // Where the finished file ended up, after all post-processing
+        "--print".to_string(),
+        format!("after_move:{}%(filepath)s", OUTPUT_PREFIX),
+    ]
+}
+
+// Arguments making yt-dlp print the SponsorBlock segments it cut, once the file is done
+pub fn sponsor_segments_args() -> Vec<String> {
+    vec![
+        "--print".to_string(),
+        format!(
+            "after_move:{}%(sponsorblock_chapters)j",
+            SPONSORBLOCK_PREFIX
+        ),
+    ]
+}
+
+// Arguments making yt-dlp print the metadata used for tagging, once the file is done
+pub fn metadata_args() -> Vec<String> {
+    vec![
+        "--print".to_string(),
+        format!(
+            "after_move:{}%(.{{title,artist,track,album,uploader,channel,upload_date,release_year,genre}})j",
+            METADATA_PREFIX
+        ),
+    ]
+}
+
+// Fields of yt-dlp's download progress hook that we care about.
+// Numbers are read as f64 because some downloaders report estimates as floats.
+#[derive(Debug, Deserialize)]
+struct DownloadHook {
+    status: String,
+    downloaded_bytes: Option<f64>,
+    total_bytes: Option<f64>,
+    total_bytes_estimate: Option<f64>,
+    speed: Option<f64>,
+    eta: Option<f64>,
+    fragment_index: Option<f64>,
+    fragment_count: Option<f64>,
+    tmpfilename: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PostprocessHook {
+    status: String,
+    postprocessor: Option<String>,
+}
+
+pub fn parse_progress_line(line: &str, id: &str) -> Option<DownloadProgress> {
+    let line = line.trim();
+
+    if let Some(json) = line.strip_prefix(DOWNLOAD_PREFIX) {
+        let hook: DownloadHook = serde_json::from_str(json).ok()?;
+        return Some(download_progress(hook, id));
+    }
+
+    if let Some(json) = line.strip_prefix(POSTPROCESS_PREFIX) {
+        let hook: PostprocessHook = serde_json::from_str(json).ok()?;
+        return postprocess_progress(hook, id);
+    }
+
+    None
+}
+
+// Final file path printed by yt-dlp once the download is complete
+pub fn parse_output_line(line: &str) -> Option<String> {
+    line.trim_end()
+        .strip_prefix(OUTPUT_PREFIX)
+        .filter(|path| !path.is_empty())
+        .map(|path| path.to_string())
+}
+
+// Segments printed by sponsor_segments_args; yt-dlp prints "NA" when there were none
+pub fn parse_sponsor_segments_line(line: &str) -> Option<Vec<SponsorSegment>> {
+    let json = line.trim().strip_prefix(SPONSORBLOCK_PREFIX)?;
+    Some(serde_json::from_str(json).unwrap_or_default())
+}
+
+pub fn parse_metadata_line(line: &str) -> Option<VideoMetadata> {
+    let json = line.trim().strip_prefix(METADATA_PREFIX)?;
+    serde_json::from_str(json).ok()
+}
+
+// "[SplitChapters] Splitting video by chapters; 12 chapters found"
+pub fn parse_chapter_count(line: &str) -> Option<u32> {
+    line.trim()
+        .strip_prefix(SPLIT_CHAPTERS_PREFIX)?
+        .strip_prefix("Splitting video by chapters; ")?
+        .strip_suffix(" chapters found")?
+        .parse()
+        .ok()
+}
+
+// "[SplitChapters] Chapter 001; Destination: /path/01 - Intro.mp3", printed
+// as each track is written
+pub fn parse_chapter_track(line: &str) -> Option<(u32, String)> {
+    let (number, path) = line
+        .trim_end()
+        .strip_prefix(SPLIT_CHAPTERS_PREFIX)?
+        .strip_prefix("Chapter ")?
+        .split_once("; Destination: ")?;
+
+    Some((number.parse().ok()?, path.to_string()))
+}
+
+fn download_progress(hook: DownloadHook, id: &str) -> DownloadProgress {
+    let downloaded = hook.downloaded_bytes.map(|b| b as u64);
+    let total = hook
+        .total_bytes
+        .or(hook.total_bytes_estimate)
+        .map(|b| b as u64);
+
+    let percent = match (downloaded, total, hook.fragment_index, hook.fragment_count) {
+        (Some(done), Some(total), _, _) if total > 0 => done as f64 / total as f64 * 100.0,
+        (_, _, Some(index), Some(count)) if count > 0.0 => index / count * 100.0,
+        _ => 0.0,
+    };
+
+    let finished = hook.status == "finished";
+    let status = if finished {
+        DownloadStatus::Processing
+    } else {
+        DownloadStatus::Downloading
+    };
+
+    DownloadProgress {
+        speed: hook.speed.map(|s| format!("{}/s", format_bytes(s as u64))),
+        speed_bps: hook.speed,
+        eta: hook.eta.map(|e| format_eta(e as u64)),
+        eta_seconds: hook.eta.map(|e| e as u64),
+        downloaded: downloaded.map(format_bytes),
+        downloaded_bytes: downloaded,
+        total_size: total.map(format_bytes),
+        total_bytes: total,
+        partial_file: hook.tmpfilename,
+        ..DownloadProgress::new(
+            id,
+            status,
+            if finished { 100.0 } else { percent.min(100.0) },
+        )
+    }
+}
+
+fn postprocess_progress(hook: PostprocessHook, id: &str) -> Option<DownloadProgress> {
+    // MoveFiles runs after every download and is not worth reporting
+    if hook.status != "started" || hook.postprocessor.as_deref() == Some("MoveFiles") {
+        return None;
+    }
+
+    Some(DownloadProgress::new(id, DownloadStatus::Processing, 95.0))
+}
+
+// Format a byte count the way yt-dlp does, e.g. "10.23MiB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[0])
+    } else {
+        format!("{:.2}{}", value, UNITS[unit])
+    }
+}
+
+fn format_eta(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn printing_output_lines_keeps_ytdlp_out_of_quiet_mode() {
+        let args = progress_template_args();
+        assert!(args.iter().any(|arg| arg == "--print"));
+        assert!(args.iter().any(|arg| arg == "--no-quiet"));
+    }
+
+    #[test]
+    fn chapter_splitter_lines_are_parsed() {
+        assert_eq!(
+            parse_chapter_count("[SplitChapters] Splitting video by chapters; 12 chapters found"),
+            Some(12)
+        );
+        assert_eq!(
+            parse_chapter_track(
+                "[SplitChapters] Chapter 101; Destination: /tmp/Song/101 - Outro; Reprise.mp3\n"
+            ),
+            Some((101, "/tmp/Song/101 - Outro; Reprise.mp3".to_string()))
+        );
+        assert_eq!(
+            parse_chapter_track("[download] Destination: /tmp/Song.webm"),
+            None
+        );
+    }
+}
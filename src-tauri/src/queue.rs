@@ -0,0 +1,197 @@
+use crate::downloader::{DownloadItem, DownloadOptions, DownloadProgress, DownloadStatus};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+// Progress-only updates are written at most this often
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub item: DownloadItem,
+    pub options: DownloadOptions,
+    pub download_folder: String,
+    pub status: DownloadStatus,
+    pub last_progress: Option<DownloadProgress>,
+    pub partial_file: Option<String>,
+}
+
+impl QueuedJob {
+    pub fn new(item: DownloadItem, options: DownloadOptions, download_folder: String) -> Self {
+        Self {
+            item,
+            options,
+            download_folder,
+            status: DownloadStatus::Pending,
+            last_progress: None,
+            partial_file: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct QueueInner {
+    path: Option<PathBuf>,
+    jobs: Vec<QueuedJob>,
+    last_flush: Option<Instant>,
+}
+
+// Queued, running, paused and failed downloads, mirrored to queue.json
+// in the app data directory so they survive a restart
+#[derive(Default)]
+pub struct QueueStore {
+    inner: Mutex<QueueInner>,
+}
+
+impl QueueStore {
+    // Load jobs left over from the previous run
+    pub fn load(&self, app_handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let data_dir = app_handle.path().app_data_dir()?;
+        fs::create_dir_all(&data_dir)?;
+        let path = data_dir.join("queue.json");
+
+        let mut jobs = if path.exists() {
+            Self::read_jobs(&path)
+        } else {
+            Vec::new()
+        };
+
+        // Nothing is running yet, so interrupted jobs become resumable
+        for job in &mut jobs {
+            if matches!(
+                job.status,
+                DownloadStatus::Pending | DownloadStatus::Downloading | DownloadStatus::Processing
+            ) {
+                job.status = DownloadStatus::Paused;
+                if let Some(progress) = &mut job.last_progress {
+                    progress.status = DownloadStatus::Paused;
+                }
+            }
+        }
+
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        inner.path = Some(path);
+        inner.jobs = jobs;
+        Self::flush(&mut inner);
+
+        Ok(())
+    }
+
+    // A file that cannot be read is moved aside, so it is not overwritten and the
+    // queue still works from an empty list
+    fn read_jobs(path: &Path) -> Vec<QueuedJob> {
+        let parsed = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|e| e.to_string()));
+
+        match parsed {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                let backup = path.with_extension("json.corrupt");
+                eprintln!(
+                    "Failed to read download queue, moving it to {}: {}",
+                    backup.display(),
+                    e
+                );
+                if let Err(e) = fs::rename(path, &backup) {
+                    eprintln!("Failed to move aside download queue: {}", e);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn enqueue(&self, job: QueuedJob) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.jobs.retain(|j| j.item.job_id() != job.item.job_id());
+            inner.jobs.push(job);
+            Self::flush(&mut inner);
+        }
+    }
+
+    // Track a progress event, dropping jobs that completed or were cancelled
+    pub fn record(&self, progress: &DownloadProgress) {
+        let Ok(mut inner) = self.inner.lock() else {
+            return;
+        };
+
+        if matches!(
+            progress.status,
+            DownloadStatus::Completed | DownloadStatus::Cancelled
+        ) {
+            inner.jobs.retain(|j| j.item.job_id() != progress.id);
+            Self::flush(&mut inner);
+            return;
+        }
+
+        let Some(job) = inner
+            .jobs
+            .iter_mut()
+            .find(|j| j.item.job_id() == progress.id)
+        else {
+            return;
+        };
+
+        let status_changed = job.status != progress.status;
+        job.status = progress.status.clone();
+        if progress.partial_file.is_some() {
+            job.partial_file = progress.partial_file.clone();
+        }
+        job.last_progress = Some(progress.clone());
+
+        let due = inner
+            .last_flush
+            .is_none_or(|last| last.elapsed() >= FLUSH_INTERVAL);
+        if status_changed || due {
+            Self::flush(&mut inner);
+        }
+    }
+
+    pub fn unfinished(&self) -> Vec<QueuedJob> {
+        self.inner
+            .lock()
+            .map(|inner| inner.jobs.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn find(&self, ids: &[String]) -> Vec<QueuedJob> {
+        self.inner
+            .lock()
+            .map(|inner| {
+                inner
+                    .jobs
+                    .iter()
+                    .filter(|j| ids.iter().any(|id| id == j.item.job_id()))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn remove(&self, ids: &[String]) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner
+                .jobs
+                .retain(|j| !ids.iter().any(|id| id == j.item.job_id()));
+            Self::flush(&mut inner);
+        }
+    }
+
+    fn flush(inner: &mut QueueInner) {
+        let Some(path) = &inner.path else {
+            return;
+        };
+
+        let result = serde_json::to_string_pretty(&inner.jobs)
+            .map_err(|e| e.to_string())
+            .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            eprintln!("Failed to save download queue: {}", e);
+        }
+        inner.last_flush = Some(Instant::now());
+    }
+}
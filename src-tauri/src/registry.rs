@@ -0,0 +1,106 @@
+use crate::downloader::DownloadProgress;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+const RUNNING: u8 = 0;
+const PAUSED: u8 = 1;
+const CANCELLED: u8 = 2;
+
+// Run state and latest progress of a single download
+#[derive(Debug, Default)]
+pub struct DownloadControl {
+    state: AtomicU8,
+    last_progress: Mutex<Option<DownloadProgress>>,
+}
+
+impl DownloadControl {
+    pub fn cancel(&self) {
+        self.state.store(CANCELLED, Ordering::Relaxed);
+    }
+
+    // Returns false if the download is already paused or cancelled
+    pub fn pause(&self) -> bool {
+        self.state
+            .compare_exchange(RUNNING, PAUSED, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    // Returns false if the download is not paused
+    pub fn resume(&self) -> bool {
+        self.state
+            .compare_exchange(PAUSED, RUNNING, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.load(Ordering::Relaxed) == CANCELLED
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.load(Ordering::Relaxed) == PAUSED
+    }
+
+    pub fn record_progress(&self, progress: &DownloadProgress) {
+        if let Ok(mut last) = self.last_progress.lock() {
+            *last = Some(progress.clone());
+        }
+    }
+
+    pub fn last_progress(&self) -> Option<DownloadProgress> {
+        self.last_progress.lock().ok().and_then(|last| last.clone())
+    }
+}
+
+// Tracks the control of every queued or running download,
+// keyed by DownloadItem::job_id. Playlist fetches have a registry of their own.
+#[derive(Debug, Default)]
+pub struct DownloadRegistry {
+    controls: Mutex<HashMap<String, Arc<DownloadControl>>>,
+}
+
+impl DownloadRegistry {
+    // Register a fresh control for a download, replacing any stale one
+    pub fn register(&self, id: &str) -> Arc<DownloadControl> {
+        let control = Arc::new(DownloadControl::default());
+        if let Ok(mut controls) = self.controls.lock() {
+            controls.insert(id.to_string(), control.clone());
+        }
+        control
+    }
+
+    // Remove a finished download, unless the id was re-registered meanwhile
+    pub fn unregister(&self, id: &str, control: &Arc<DownloadControl>) {
+        if let Ok(mut controls) = self.controls.lock() {
+            if controls
+                .get(id)
+                .is_some_and(|current| Arc::ptr_eq(current, control))
+            {
+                controls.remove(id);
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<DownloadControl>> {
+        self.controls.lock().ok()?.get(id).cloned()
+    }
+
+    // Cancel one download, returning false if it is not known
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.get(id) {
+            Some(control) => {
+                control.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn cancel_all(&self) {
+        if let Ok(controls) = self.controls.lock() {
+            for control in controls.values() {
+                control.cancel();
+            }
+        }
+    }
+}
@@ -1,13 +1,94 @@
+use crate::cover::CoverArtOptions;
+use crate::filename::CollisionPolicy;
+use crate::formats::{
+    default_quality_presets, AudioFormat, DownloadMode, QualityPreset, VideoOptions,
+};
+use crate::loudness::LoudnessOptions;
+use crate::lyrics::LyricsOptions;
+use crate::playlist::PlaylistOptions;
+use crate::sponsorblock::SponsorBlockOptions;
+use crate::tagging::TaggingOptions;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tauri::AppHandle;
 use std::fs;
+use std::time::Duration;
 // Removed tauri::api::path::app_config_dir - using dirs crate instead
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub download_folder: String,
     pub parallel_downloads: u8,
+    #[serde(default)]
+    pub mode: DownloadMode,
+    #[serde(default)]
+    pub audio_format: AudioFormat,
+    #[serde(default = "default_quality_presets")]
+    pub quality_presets: Vec<QualityPreset>,
+    // Name of the preset used when an item does not pick one
+    #[serde(default)]
+    pub default_preset: Option<String>,
+    #[serde(default)]
+    pub video: VideoOptions,
+    // Skip videos whose ids are already in the download archive, and add
+    // finished downloads to it
+    #[serde(default)]
+    pub use_download_archive: bool,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    // See template.rs for the available tokens
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    #[serde(default)]
+    pub folder_template: String,
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    #[serde(default)]
+    pub sponsorblock: SponsorBlockOptions,
+    #[serde(default)]
+    pub loudness: LoudnessOptions,
+    #[serde(default)]
+    pub tagging: TaggingOptions,
+    #[serde(default)]
+    pub cover_art: CoverArtOptions,
+    #[serde(default)]
+    pub lyrics: LyricsOptions,
+    #[serde(default)]
+    pub playlist: PlaylistOptions,
+}
+
+fn default_filename_template() -> String {
+    "{title}".to_string()
+}
+
+// How often transient download failures are retried
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    // Delay before the first retry, doubled for each further attempt
+    pub initial_delay_secs: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay_secs: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    const MAX_DELAY_SECS: u64 = 60;
+
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        Duration::from_secs(
+            self.initial_delay_secs
+                .saturating_mul(factor)
+                .min(Self::MAX_DELAY_SECS),
+        )
+    }
 }
 
 impl Default for Settings {
@@ -15,10 +96,32 @@ impl Default for Settings {
         Self {
             download_folder: get_default_download_folder(),
             parallel_downloads: 1,
+            mode: DownloadMode::default(),
+            audio_format: AudioFormat::default(),
+            quality_presets: default_quality_presets(),
+            default_preset: None,
+            video: VideoOptions::default(),
+            use_download_archive: false,
+            retry: RetryPolicy::default(),
+            filename_template: default_filename_template(),
+            folder_template: String::new(),
+            collision_policy: CollisionPolicy::default(),
+            sponsorblock: SponsorBlockOptions::default(),
+            loudness: LoudnessOptions::default(),
+            tagging: TaggingOptions::default(),
+            cover_art: CoverArtOptions::default(),
+            lyrics: LyricsOptions::default(),
+            playlist: PlaylistOptions::default(),
         }
     }
 }
 
+impl Settings {
+    pub fn find_preset(&self, name: &str) -> Option<&QualityPreset> {
+        self.quality_presets.iter().find(|p| p.name == name)
+    }
+}
+
 // Get the settings file path
 pub fn get_settings_path(_app_handle: &AppHandle) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let config_dir = dirs::config_dir()
@@ -143,3 +246,59 @@ pub fn validate_parallel_downloads(count: u8) -> Result<(), String> {
     
     Ok(())
 }
+
+
+// Validate quality presets and the default preset reference
+pub fn validate_quality_presets(settings: &Settings) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+
+    for preset in &settings.quality_presets {
+        preset.validate()?;
+
+        if !names.insert(preset.name.as_str()) {
+            return Err(format!("Duplicate preset name '{}'", preset.name));
+        }
+    }
+
+    if let Some(name) = &settings.default_preset {
+        if settings.find_preset(name).is_none() {
+            return Err(format!("Default preset '{}' does not exist", name));
+        }
+    }
+
+    Ok(())
+}
+
+
+// Validate retry settings
+pub fn validate_retry_policy(policy: &RetryPolicy) -> Result<(), String> {
+    if policy.max_retries > 10 {
+        return Err("Retries cannot exceed 10".to_string());
+    }
+
+    if policy.initial_delay_secs < 1 || policy.initial_delay_secs > 60 {
+        return Err("Retry delay must be between 1 and 60 seconds".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_a_minute() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_delay_secs: 2,
+        };
+
+        let delays: Vec<u64> = (1..=7)
+            .map(|attempt| policy.delay_for(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [2, 4, 8, 16, 32, 60, 60]);
+        // The shift is capped, so huge attempt numbers cannot overflow
+        assert_eq!(policy.delay_for(u32::MAX).as_secs(), 60);
+    }
+}
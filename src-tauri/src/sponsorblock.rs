@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+// Public SponsorBlock server, used unless settings point elsewhere
+const DEFAULT_API_URL: &str = "https://sponsor.ajay.app";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SponsorBlockMode {
+    #[default]
+    Off,
+    // Cut the segments out of the file
+    Remove,
+    // Keep the audio but add a chapter for each segment
+    Mark,
+}
+
+// Community-flagged segment kinds, named as in the SponsorBlock API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsorCategory {
+    Sponsor,
+    Intro,
+    Outro,
+    Selfpromo,
+    // Non-music parts of music videos
+    MusicOfftopic,
+}
+
+impl SponsorCategory {
+    fn as_ytdlp_arg(&self) -> &'static str {
+        match self {
+            SponsorCategory::Sponsor => "sponsor",
+            SponsorCategory::Intro => "intro",
+            SponsorCategory::Outro => "outro",
+            SponsorCategory::Selfpromo => "selfpromo",
+            SponsorCategory::MusicOfftopic => "music_offtopic",
+        }
+    }
+}
+
+fn default_categories() -> Vec<SponsorCategory> {
+    vec![
+        SponsorCategory::Sponsor,
+        SponsorCategory::Selfpromo,
+        SponsorCategory::MusicOfftopic,
+    ]
+}
+
+fn default_api_url() -> String {
+    DEFAULT_API_URL.to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SponsorBlockOptions {
+    #[serde(default)]
+    pub mode: SponsorBlockMode,
+    #[serde(default = "default_categories")]
+    pub categories: Vec<SponsorCategory>,
+    // Configurable so a local stand-in can replace the public server
+    #[serde(default = "default_api_url")]
+    pub api_url: String,
+}
+
+impl Default for SponsorBlockOptions {
+    fn default() -> Self {
+        Self {
+            mode: SponsorBlockMode::default(),
+            categories: default_categories(),
+            api_url: default_api_url(),
+        }
+    }
+}
+
+impl SponsorBlockOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.mode != SponsorBlockMode::Off && !self.categories.is_empty()
+    }
+
+    pub fn ytdlp_args(&self) -> Vec<String> {
+        if !self.is_enabled() {
+            return Vec::new();
+        }
+
+        let flag = match self.mode {
+            SponsorBlockMode::Remove => "--sponsorblock-remove",
+            _ => "--sponsorblock-mark",
+        };
+        let categories = self
+            .categories
+            .iter()
+            .map(|c| c.as_ytdlp_arg())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        vec![
+            flag.to_string(),
+            categories,
+            "--sponsorblock-api".to_string(),
+            self.api_url.trim_end_matches('/').to_string(),
+        ]
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.api_url.starts_with("http://") && !self.api_url.starts_with("https://") {
+            return Err("SponsorBlock API URL must start with http:// or https://".to_string());
+        }
+
+        if self.mode != SponsorBlockMode::Off && self.categories.is_empty() {
+            return Err("Choose at least one SponsorBlock category".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// A segment SponsorBlock cut from the file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SponsorSegment {
+    pub category: String,
+    // Offsets into the original video, in seconds
+    pub start_time: f64,
+    pub end_time: f64,
+}
@@ -0,0 +1,322 @@
+use crate::downloader::DownloadError;
+use crate::ffmpeg::write_tags;
+use crate::formats::AudioFormat;
+use crate::registry::DownloadControl;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// Tags set by the user on an item before it is downloaded; they win over everything else
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagOverrides {
+    #[serde(default)]
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub album: Option<String>,
+    #[serde(default)]
+    pub track_number: Option<u32>,
+    #[serde(default)]
+    pub year: Option<String>,
+    #[serde(default)]
+    pub genre: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaggingOptions {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Used when neither the video nor the item has a genre
+    #[serde(default)]
+    pub default_genre: Option<String>,
+}
+
+impl Default for TaggingOptions {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            default_genre: None,
+        }
+    }
+}
+
+// Everything known about an item's tags before it is downloaded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagPlan {
+    // From the playlist the item was picked from
+    pub album: Option<String>,
+    pub track_number: Option<u32>,
+    pub track_total: Option<u32>,
+    pub default_genre: Option<String>,
+    #[serde(default)]
+    pub overrides: TagOverrides,
+}
+
+// Metadata yt-dlp prints once the file is done; see progress::metadata_args
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct VideoMetadata {
+    pub title: Option<String>,
+    // Only set for music YouTube recognises
+    pub artist: Option<String>,
+    pub track: Option<String>,
+    pub album: Option<String>,
+    pub uploader: Option<String>,
+    pub channel: Option<String>,
+    // YYYYMMDD
+    pub upload_date: Option<String>,
+    pub release_year: Option<u32>,
+    pub genre: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Tags {
+    artist: Option<String>,
+    title: Option<String>,
+    album: Option<String>,
+    track: Option<String>,
+    year: Option<String>,
+    genre: Option<String>,
+}
+
+impl Tags {
+    fn into_ffmpeg_metadata(self) -> Vec<(&'static str, String)> {
+        [
+            ("artist", self.artist),
+            ("title", self.title),
+            ("album", self.album),
+            ("track", self.track),
+            ("date", self.year),
+            ("genre", self.genre),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect()
+    }
+}
+
+// Tag the finished files. When they are chapter tracks, the video becomes the
+// album and each chapter a track on it.
+pub async fn apply_tags(
+    app: &AppHandle,
+    control: &DownloadControl,
+    plan: &TagPlan,
+    metadata: &VideoMetadata,
+    files: &[PathBuf],
+    chapter_tracks: bool,
+    format: AudioFormat,
+) -> Result<(), DownloadError> {
+    let base = base_tags(plan, metadata);
+
+    if !chapter_tracks {
+        for file in files {
+            write_tags(
+                app,
+                control,
+                file,
+                format,
+                &base.clone().into_ffmpeg_metadata(),
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
+    let total = files.len();
+    for (i, file) in files.iter().enumerate() {
+        let tags = Tags {
+            title: Some(chapter_title(file)),
+            album: plan.overrides.album.clone().or_else(|| base.title.clone()),
+            track: Some(format!("{}/{}", i + 1, total)),
+            ..base.clone()
+        };
+        write_tags(app, control, file, format, &tags.into_ffmpeg_metadata()).await?;
+    }
+
+    Ok(())
+}
+
+fn base_tags(plan: &TagPlan, metadata: &VideoMetadata) -> Tags {
+    let overrides = &plan.overrides;
+    let (split_artist, split_title) = metadata
+        .title
+        .as_deref()
+        .map(split_artist_title)
+        .unwrap_or_default();
+
+    // YouTube's own music metadata beats guessing from the title
+    let artist = overrides
+        .artist
+        .clone()
+        .or_else(|| metadata.artist.clone())
+        .or(split_artist)
+        .or_else(|| {
+            metadata
+                .uploader
+                .as_ref()
+                .or(metadata.channel.as_ref())
+                .map(|name| name.trim_end_matches(" - Topic").to_string())
+        });
+    let title = overrides
+        .title
+        .clone()
+        .or_else(|| metadata.track.clone())
+        .or(split_title);
+
+    let track = overrides
+        .track_number
+        .or(plan.track_number)
+        .map(|number| match plan.track_total {
+            Some(total) => format!("{}/{}", number, total),
+            None => number.to_string(),
+        });
+    let year = overrides
+        .year
+        .clone()
+        .or_else(|| metadata.release_year.map(|year| year.to_string()))
+        .or_else(|| {
+            metadata
+                .upload_date
+                .as_ref()
+                .map(|d| d.chars().take(4).collect())
+        });
+
+    Tags {
+        artist,
+        title,
+        album: overrides
+            .album
+            .clone()
+            .or_else(|| plan.album.clone())
+            .or_else(|| metadata.album.clone()),
+        track,
+        year,
+        genre: overrides
+            .genre
+            .clone()
+            .or_else(|| metadata.genre.clone())
+            .or_else(|| plan.default_genre.clone()),
+    }
+}
+
+// Split "Artist - Title (Official Video)" into ("Artist", "Title").
+// Titles without a separator are kept whole, minus the noise.
+fn split_artist_title(title: &str) -> (Option<String>, Option<String>) {
+    let cleaned = strip_noise(title);
+
+    for separator in [" - ", " – ", " — ", " | "] {
+        if let Some((artist, track)) = cleaned.split_once(separator) {
+            let (artist, track) = (artist.trim(), track.trim());
+            if !artist.is_empty() && !track.is_empty() {
+                return (Some(artist.to_string()), Some(track.to_string()));
+            }
+        }
+    }
+
+    let cleaned = cleaned.trim();
+    (None, (!cleaned.is_empty()).then(|| cleaned.to_string()))
+}
+
+// Remove bracketed notes like "(Official Music Video)" or "[Lyrics]"
+fn strip_noise(title: &str) -> String {
+    const NOISE: [&str; 9] = [
+        "official",
+        "video",
+        "audio",
+        "lyric",
+        "visualizer",
+        "hd",
+        "4k",
+        "remastered",
+        "mv",
+    ];
+
+    let mut result = String::new();
+    let mut rest = title;
+
+    while let Some(start) = rest.find(['(', '[']) {
+        let close = if rest[start..].starts_with('(') {
+            ')'
+        } else {
+            ']'
+        };
+        let Some(len) = rest[start..].find(close) else {
+            break;
+        };
+
+        // Whole words only, so "(feat. Audioslave)" stays; plurals like "Lyrics" count
+        let inner = rest[start + 1..start + len].to_lowercase();
+        let is_noise = inner
+            .split(|c: char| !c.is_alphanumeric())
+            .map(|word| word.strip_suffix('s').unwrap_or(word))
+            .any(|word| NOISE.contains(&word));
+
+        result.push_str(&rest[..start]);
+        if !is_noise {
+            result.push_str(&rest[start..=start + len]);
+        }
+        rest = &rest[start + len + 1..];
+    }
+
+    result.push_str(rest);
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Chapter files are named "NN - Chapter title.ext"
+fn chapter_title(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match stem.split_once(" - ") {
+        Some((number, title)) if number.chars().all(|c| c.is_ascii_digit()) => title.to_string(),
+        _ => stem,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noise_in_brackets_is_removed() {
+        assert_eq!(
+            strip_noise("Artist - Song (Official Music Video)"),
+            "Artist - Song"
+        );
+        assert_eq!(strip_noise("Artist - Song [Lyrics] (HD)"), "Artist - Song");
+        assert_eq!(strip_noise("Song (Official Audio) [4K]"), "Song");
+    }
+
+    #[test]
+    fn other_brackets_are_kept() {
+        assert_eq!(
+            strip_noise("Artist - Song (Live at Wembley)"),
+            "Artist - Song (Live at Wembley)"
+        );
+        assert_eq!(strip_noise("Song [Remix] (2019)"), "Song [Remix] (2019)");
+        // An unclosed bracket is left as it is
+        assert_eq!(strip_noise("Song (Official"), "Song (Official");
+    }
+
+    #[test]
+    fn words_that_merely_start_like_noise_are_kept() {
+        assert_eq!(
+            strip_noise("Cochise (feat. Audioslave)"),
+            "Cochise (feat. Audioslave)"
+        );
+        assert_eq!(
+            strip_noise("Song (Lyrically Speaking)"),
+            "Song (Lyrically Speaking)"
+        );
+        assert_eq!(
+            strip_noise("Song (Videogames Remix)"),
+            "Song (Videogames Remix)"
+        );
+    }
+}
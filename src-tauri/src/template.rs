@@ -0,0 +1,261 @@
+use crate::filename::{sanitize_component, sanitize_relative_path};
+use crate::playlist::Item;
+use std::path::Path;
+
+// Tokens usable in filename and folder templates, e.g. "{index:02} - {title}"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Artist,
+    Title,
+    Uploader,
+    Playlist,
+    Index,
+    UploadDate,
+    Id,
+}
+
+impl Token {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "artist" => Some(Token::Artist),
+            "title" => Some(Token::Title),
+            "uploader" => Some(Token::Uploader),
+            "playlist" => Some(Token::Playlist),
+            "index" => Some(Token::Index),
+            "upload_date" => Some(Token::UploadDate),
+            "id" => Some(Token::Id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    // Zero-padded to `width` digits when given, e.g. {index:02}
+    Token { token: Token, width: Option<usize> },
+}
+
+// Values known before yt-dlp runs. Anything missing is left for yt-dlp
+// to fill in from the video's metadata.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    pub title: Option<String>,
+    pub id: Option<String>,
+    pub uploader: Option<String>,
+    pub playlist: Option<String>,
+    pub index: Option<u32>,
+}
+
+impl TemplateContext {
+    pub fn from_item(item: &Item, playlist: Option<String>, index: Option<u32>) -> Self {
+        Self {
+            title: Some(item.title.clone()),
+            id: Some(item.id.clone()),
+            uploader: item.uploader.clone(),
+            playlist,
+            index,
+        }
+    }
+}
+
+fn parse(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        literal.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| format!("Unclosed '{{' in template '{}'", template))?;
+
+        let spec = &rest[start + 1..end];
+        let (name, width) = match spec.split_once(':') {
+            Some((name, width)) => {
+                let width = width
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|w| *w <= 10)
+                    .ok_or_else(|| format!("Invalid width in '{{{}}}'", spec))?;
+                (name, Some(width))
+            }
+            None => (spec, None),
+        };
+        let token =
+            Token::from_name(name).ok_or_else(|| format!("Unknown token '{{{}}}'", name))?;
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(std::mem::take(&mut literal)));
+        }
+        segments.push(Segment::Token { token, width });
+        rest = &rest[end + 1..];
+    }
+
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+pub fn validate_templates(folder_template: &str, filename_template: &str) -> Result<(), String> {
+    if filename_template.trim().is_empty() {
+        return Err("Filename template cannot be empty".to_string());
+    }
+
+    if filename_template.contains('/') || filename_template.contains('\\') {
+        return Err(
+            "Filename template cannot contain folders; use the folder template".to_string(),
+        );
+    }
+
+    if folder_template.starts_with('/')
+        || folder_template.starts_with('\\')
+        || folder_template.contains(':')
+        || folder_template.split(['/', '\\']).any(|part| part == "..")
+    {
+        return Err(
+            "Folder template must be a relative path inside the download folder".to_string(),
+        );
+    }
+
+    parse(folder_template)?;
+    parse(filename_template)?;
+    Ok(())
+}
+
+// Build the relative yt-dlp output template (ending in ".%(ext)s") for a download
+pub fn to_ytdlp_template(
+    folder_template: &str,
+    filename_template: &str,
+    context: &TemplateContext,
+) -> Result<String, String> {
+    let file = render(filename_template, context, &ytdlp_value, &escape_literal)?;
+    let folder = render(folder_template, context, &ytdlp_value, &escape_literal)?;
+
+    Ok(join(&folder, &format!("{}.%(ext)s", file)))
+}
+
+// Render the relative output path for display before anything is downloaded
+pub fn render_preview(
+    folder_template: &str,
+    filename_template: &str,
+    context: &TemplateContext,
+    extension: &str,
+) -> Result<String, String> {
+    let file = render(filename_template, context, &preview_value, &str::to_string)?;
+    let folder = render(folder_template, context, &preview_value, &str::to_string)?;
+    let path = join(&folder, &format!("{}.{}", file, extension));
+
+    // The downloader sanitizes the final path the same way
+    Ok(sanitize_relative_path(Path::new(&path))
+        .to_string_lossy()
+        .to_string())
+}
+
+fn render(
+    template: &str,
+    context: &TemplateContext,
+    value: &dyn Fn(Token, Option<usize>, &TemplateContext) -> String,
+    literal: &dyn Fn(&str) -> String,
+) -> Result<String, String> {
+    let rendered = parse(template)?
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => literal(&text),
+            Segment::Token { token, width } => value(token, width, context),
+        })
+        .collect();
+
+    Ok(rendered)
+}
+
+// Known values are inlined; the rest become yt-dlp fields
+fn ytdlp_value(token: Token, width: Option<usize>, context: &TemplateContext) -> String {
+    match token {
+        Token::Playlist => match &context.playlist {
+            Some(playlist) => escape_literal(&sanitize_component(playlist)),
+            None => "%(playlist_title,playlist)s".to_string(),
+        },
+        Token::Index => match (context.index, width) {
+            (Some(index), Some(width)) => format!("{:0width$}", index, width = width),
+            (Some(index), None) => index.to_string(),
+            (None, Some(width)) => format!("%(playlist_index)0{}d", width),
+            (None, None) => "%(playlist_index)d".to_string(),
+        },
+        Token::Title => "%(title)s".to_string(),
+        Token::Artist => "%(artist,creator,uploader)s".to_string(),
+        Token::Uploader => "%(uploader)s".to_string(),
+        Token::UploadDate => "%(upload_date>%Y-%m-%d)s".to_string(),
+        Token::Id => "%(id)s".to_string(),
+    }
+}
+
+// Values shown in the preview; metadata only yt-dlp knows is shown as "NA", as yt-dlp does
+fn preview_value(token: Token, width: Option<usize>, context: &TemplateContext) -> String {
+    let value = match token {
+        Token::Title => context.title.clone(),
+        Token::Id => context.id.clone(),
+        Token::Artist | Token::Uploader => context.uploader.clone(),
+        Token::Playlist => context.playlist.clone(),
+        Token::Index => context
+            .index
+            .map(|index| format!("{:0width$}", index, width = width.unwrap_or(0))),
+        Token::UploadDate => None,
+    };
+
+    sanitize_component(&value.unwrap_or_else(|| "NA".to_string()))
+}
+
+// yt-dlp treats '%' as the start of a field
+fn escape_literal(text: &str) -> String {
+    text.replace('%', "%%")
+}
+
+fn join(folder: &str, file: &str) -> String {
+    let folder = folder.trim_matches(|c| c == '/' || c == '\\');
+    if folder.is_empty() {
+        file.to_string()
+    } else {
+        format!("{}/{}", folder, file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_with_known_tokens_are_accepted() {
+        assert!(validate_templates("{playlist}", "{index:02} - {title}").is_ok());
+        assert!(validate_templates("", "{artist} - {title} [{id}]").is_ok());
+        assert!(validate_templates("Music/{uploader}", "{upload_date} {title}").is_ok());
+    }
+
+    #[test]
+    fn bad_templates_are_rejected() {
+        let cases = [
+            ("", " "),
+            ("", "{title}/{id}"),
+            ("/music", "{title}"),
+            ("C:\\music", "{title}"),
+            ("music/../..", "{title}"),
+            ("", "{album}"),
+            ("", "{title"),
+            ("", "{index:xx}"),
+            ("", "{index:11}"),
+        ];
+
+        for (folder, filename) in cases {
+            assert!(
+                validate_templates(folder, filename).is_err(),
+                "{:?} / {:?}",
+                folder,
+                filename
+            );
+        }
+    }
+}
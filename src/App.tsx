@@ -2,6 +2,7 @@ import { BrowserRouter as Router, Routes, Route, Navigate } from 'react-router-d
 import { Toaster } from 'react-hot-toast';
 import { ThemeProvider } from './contexts/ThemeContext';
 import Navigation from './components/Navigation';
+import ResumePrompt from './components/ResumePrompt';
 import Home from './pages/Home';
 import Downloads from './pages/Downloads';
 import SettingsPage from './pages/SettingsPage';
@@ -13,6 +14,7 @@ function App() {
         <div className="min-h-screen bg-gray-50 dark:bg-gray-900 transition-colors">
           <Navigation />
           <main className="container mx-auto px-4 py-8">
+            <ResumePrompt />
             <Routes>
               <Route path="/" element={<Home />} />
               <Route path="/downloads" element={<Downloads />} />
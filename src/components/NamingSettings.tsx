@@ -0,0 +1,133 @@
+import React, { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { FileText } from 'lucide-react';
+import { Item, Settings as SettingsType } from '../types';
+import { usePlaylistStore } from '../store/playlistStore';
+import { describeError } from '../utils/errors';
+
+interface NamingSettingsProps {
+    settings: SettingsType;
+    onUpdateSettings: (settings: SettingsType) => void;
+}
+
+// Used for the preview until a playlist has been fetched
+const sampleItem: Item = {
+    id: 'dQw4w9WgXcQ',
+    title: 'Never Gonna Give You Up',
+    url: 'https://www.youtube.com/watch?v=dQw4w9WgXcQ',
+    uploader: 'Rick Astley',
+    playlist_index: 1,
+};
+
+const NamingSettings: React.FC<NamingSettingsProps> = ({ settings, onUpdateSettings }) => {
+    const { items, playlist } = usePlaylistStore();
+    const [folderTemplate, setFolderTemplate] = useState(settings.folder_template ?? '');
+    const [filenameTemplate, setFilenameTemplate] = useState(settings.filename_template ?? '{title}');
+    const [preview, setPreview] = useState<string | null>(null);
+    const [error, setError] = useState<string | null>(null);
+
+    useEffect(() => {
+        setFolderTemplate(settings.folder_template ?? '');
+        setFilenameTemplate(settings.filename_template ?? '{title}');
+    }, [settings.folder_template, settings.filename_template]);
+
+    // Re-render the preview shortly after the user stops typing
+    useEffect(() => {
+        const item = items[0] ?? sampleItem;
+        const timer = setTimeout(() => {
+            invoke<string>('preview_output_path', {
+                item,
+                settings: {
+                    ...settings,
+                    folder_template: folderTemplate,
+                    filename_template: filenameTemplate,
+                },
+                playlistTitle: playlist?.title ?? (items.length === 0 ? 'My Playlist' : null),
+                playlistIndex: item.playlist_index ?? null,
+            })
+                .then((path) => {
+                    setPreview(path);
+                    setError(null);
+                })
+                .catch((e) => {
+                    setPreview(null);
+                    setError(describeError(e, 'Invalid template'));
+                });
+        }, 300);
+
+        return () => clearTimeout(timer);
+    }, [folderTemplate, filenameTemplate, settings, items, playlist]);
+
+    const handleSave = () => {
+        if (error) {
+            return;
+        }
+
+        if (folderTemplate !== settings.folder_template || filenameTemplate !== settings.filename_template) {
+            onUpdateSettings({
+                ...settings,
+                folder_template: folderTemplate,
+                filename_template: filenameTemplate,
+            });
+        }
+    };
+
+    const inputClass = 'w-full px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-sm font-mono text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+    return (
+        <div className="bg-white dark:bg-gray-800 rounded-lg p-6 border border-gray-200 dark:border-gray-700 transition-colors">
+            <div className="flex items-center space-x-3 mb-4">
+                <FileText className="text-blue-600 dark:text-blue-400" size={20} />
+                <h3 className="text-lg font-medium text-gray-900 dark:text-white">
+                    File Naming
+                </h3>
+            </div>
+
+            <div className="space-y-4">
+                <div>
+                    <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                        Folder
+                    </label>
+                    <input
+                        type="text"
+                        value={folderTemplate}
+                        onChange={(e) => setFolderTemplate(e.target.value)}
+                        onBlur={handleSave}
+                        placeholder="{playlist}"
+                        className={inputClass}
+                    />
+                </div>
+
+                <div>
+                    <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
+                        File name
+                    </label>
+                    <input
+                        type="text"
+                        value={filenameTemplate}
+                        onChange={(e) => setFilenameTemplate(e.target.value)}
+                        onBlur={handleSave}
+                        placeholder="{title}"
+                        className={inputClass}
+                    />
+                </div>
+
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Tokens: {'{artist} {title} {uploader} {playlist} {index} {upload_date} {id}'}, e.g. {'{index:02}'}
+                </p>
+
+                <div className="p-3 bg-gray-50 dark:bg-gray-700 rounded border transition-colors">
+                    {error ? (
+                        <span className="text-sm text-red-600 dark:text-red-400">{error}</span>
+                    ) : (
+                        <code className="text-sm text-gray-700 dark:text-gray-300 break-all">
+                            {preview ?? '...'}
+                        </code>
+                    )}
+                </div>
+            </div>
+        </div>
+    );
+};
+
+export default NamingSettings;
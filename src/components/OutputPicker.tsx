@@ -0,0 +1,112 @@
+import React from 'react';
+import { AudioFormat, DownloadMode, ItemOutput, QueueItem, VideoFormat } from '../types';
+import { useSettingsStore } from '../store/settingsStore';
+
+interface OutputPickerProps {
+    item: QueueItem;
+    onChange: (output: ItemOutput) => void;
+}
+
+const audioFormats: AudioFormat[] = ['mp3', 'm4a', 'opus', 'flac', 'wav', 'vorbis'];
+const videoFormats: VideoFormat[] = ['mp4', 'webm', 'mkv'];
+const resolutions = [2160, 1440, 1080, 720, 480, 360];
+
+// Output choices for one queued item; "Default" leaves the setting in charge
+const OutputPicker: React.FC<OutputPickerProps> = ({ item, onChange }) => {
+    const { settings } = useSettingsStore();
+    const presets = settings.quality_presets ?? [];
+    const mode = item.mode ?? settings.mode ?? 'audio';
+    const video = item.video ?? settings.video ?? { format: 'mp4', max_resolution: null };
+    // Items listed from a playlist may still have chapters; only a known empty list rules it out
+    const hasNoChapters = item.chapters?.length === 0;
+
+    const selectClass = 'px-2 py-1 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded text-xs text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+    return (
+        // Clicks here must not toggle the card's selection
+        <div className="flex flex-wrap items-center gap-2 mt-2" onClick={(e) => e.stopPropagation()}>
+            <select
+                value={item.mode ?? ''}
+                onChange={(e) => onChange({ mode: (e.target.value || undefined) as DownloadMode | undefined })}
+                className={selectClass}
+                title="Download mode"
+            >
+                <option value="">Default ({settings.mode ?? 'audio'})</option>
+                <option value="audio">audio</option>
+                <option value="video">video</option>
+            </select>
+
+            {mode === 'audio' ? (
+                <>
+                    <select
+                        value={item.audio_format ?? ''}
+                        onChange={(e) => onChange({ audio_format: (e.target.value || undefined) as AudioFormat | undefined })}
+                        className={selectClass}
+                        title="Audio format"
+                    >
+                        <option value="">Default ({settings.audio_format})</option>
+                        {audioFormats.map((format) => (
+                            <option key={format} value={format}>{format}</option>
+                        ))}
+                    </select>
+                    {presets.length > 0 && (
+                        <select
+                            value={item.preset ?? ''}
+                            onChange={(e) => onChange({ preset: e.target.value || undefined })}
+                            className={selectClass}
+                            title="Quality preset"
+                        >
+                            <option value="">Default ({settings.default_preset ?? 'best'})</option>
+                            {presets.map((preset) => (
+                                <option key={preset.name} value={preset.name}>{preset.name}</option>
+                            ))}
+                        </select>
+                    )}
+                </>
+            ) : (
+                <>
+                    <select
+                        value={video.format}
+                        onChange={(e) => onChange({ video: { ...video, format: e.target.value as VideoFormat } })}
+                        className={selectClass}
+                        title="Video format"
+                    >
+                        {videoFormats.map((format) => (
+                            <option key={format} value={format}>{format}</option>
+                        ))}
+                    </select>
+                    <select
+                        value={video.max_resolution ?? ''}
+                        onChange={(e) => onChange({
+                            video: { ...video, max_resolution: e.target.value ? Number(e.target.value) : null },
+                        })}
+                        className={selectClass}
+                        title="Maximum resolution"
+                    >
+                        <option value="">Best</option>
+                        {resolutions.map((height) => (
+                            <option key={height} value={height}>{height}p</option>
+                        ))}
+                    </select>
+                </>
+            )}
+
+            <label
+                className={`flex items-center space-x-1 text-xs ${hasNoChapters ? 'text-gray-400 dark:text-gray-500' : 'text-gray-700 dark:text-gray-300'}`}
+                title={hasNoChapters ? 'This video has no chapters' : 'Write one file per chapter'}
+            >
+                <input
+                    type="checkbox"
+                    checked={(item.split_chapters ?? false) && !hasNoChapters}
+                    disabled={hasNoChapters}
+                    onChange={(e) => onChange({ split_chapters: e.target.checked || undefined })}
+                />
+                <span>
+                    Split chapters{item.chapters?.length ? ` (${item.chapters.length})` : ''}
+                </span>
+            </label>
+        </div>
+    );
+};
+
+export default OutputPicker;
@@ -0,0 +1,324 @@
+import React, { useEffect, useState } from 'react';
+import { Scissors, Volume2, Tag, FileText, ListVideo } from 'lucide-react';
+import {
+    CoverArtOptions,
+    LoudnessOptions,
+    LyricsOptions,
+    Settings as SettingsType,
+    SponsorBlockOptions,
+    SponsorCategory,
+} from '../types';
+
+interface ProcessingSettingsProps {
+    settings: SettingsType;
+    onUpdateSettings: (settings: SettingsType) => void;
+}
+
+// Same defaults as the backend, used until its settings have loaded
+const defaultSponsorBlock: SponsorBlockOptions = {
+    mode: 'off',
+    categories: ['sponsor', 'selfpromo', 'music_offtopic'],
+    api_url: 'https://sponsor.ajay.app',
+};
+const defaultLoudness: LoudnessOptions = { mode: 'off', target_lufs: -14, true_peak: -1 };
+const defaultTagging = { enabled: true, default_genre: null };
+const defaultCoverArt: CoverArtOptions = {
+    square_crop: true,
+    max_size: 1200,
+    jpeg_quality: 90,
+    save_cover_file: false,
+};
+const defaultLyrics: LyricsOptions = {
+    export_lrc: false,
+    export_txt: false,
+    embed: false,
+    languages: ['en'],
+    auto_captions: true,
+};
+const defaultPlaylist = { mix_item_cap: 100, fetch_timeout_secs: 300 };
+
+const sponsorCategories: { value: SponsorCategory; label: string }[] = [
+    { value: 'sponsor', label: 'Sponsors' },
+    { value: 'intro', label: 'Intros' },
+    { value: 'outro', label: 'Outros' },
+    { value: 'selfpromo', label: 'Self-promotion' },
+    { value: 'music_offtopic', label: 'Non-music parts' },
+];
+
+const inputClass = 'px-3 py-2 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded-lg text-sm text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+const Section: React.FC<{ icon: React.ElementType; title: string; children: React.ReactNode }> = ({
+    icon: Icon,
+    title,
+    children,
+}) => (
+    <div className="bg-white dark:bg-gray-800 rounded-lg p-6 border border-gray-200 dark:border-gray-700 transition-colors">
+        <div className="flex items-center space-x-3 mb-4">
+            <Icon className="text-blue-600 dark:text-blue-400" size={20} />
+            <h3 className="text-lg font-medium text-gray-900 dark:text-white">{title}</h3>
+        </div>
+        <div className="space-y-4">{children}</div>
+    </div>
+);
+
+const Toggle: React.FC<{ label: string; description?: string; checked: boolean; onChange: (checked: boolean) => void }> = ({
+    label,
+    description,
+    checked,
+    onChange,
+}) => (
+    <div className="flex items-center justify-between">
+        <div>
+            <h4 className="text-sm font-medium text-gray-900 dark:text-white">{label}</h4>
+            {description && (
+                <p className="text-sm text-gray-600 dark:text-gray-400">{description}</p>
+            )}
+        </div>
+        <button
+            onClick={() => onChange(!checked)}
+            className={`relative inline-flex h-6 w-11 flex-shrink-0 items-center rounded-full transition-colors ${checked
+                    ? 'bg-blue-600 dark:bg-blue-500'
+                    : 'bg-gray-300 dark:bg-gray-600'
+                }`}
+        >
+            <span
+                className={`inline-block h-4 w-4 transform rounded-full bg-white transition-transform ${checked ? 'translate-x-6' : 'translate-x-1'
+                    }`}
+            />
+        </button>
+    </div>
+);
+
+// A text field that saves when it loses focus, so every keystroke is not saved
+const Field: React.FC<{ label: string; value: string; type?: 'text' | 'number'; placeholder?: string; onCommit: (value: string) => void }> = ({
+    label,
+    value,
+    type = 'text',
+    placeholder,
+    onCommit,
+}) => {
+    const [draft, setDraft] = useState(value);
+
+    useEffect(() => setDraft(value), [value]);
+
+    return (
+        <label className="flex items-center justify-between text-sm font-medium text-gray-700 dark:text-gray-300">
+            <span>{label}</span>
+            <input
+                type={type}
+                value={draft}
+                placeholder={placeholder}
+                onChange={(e) => setDraft(e.target.value)}
+                onBlur={() => draft !== value && onCommit(draft)}
+                className={`${inputClass} w-48`}
+            />
+        </label>
+    );
+};
+
+const Select = <T extends string>({ label, value, options, onChange }: {
+    label: string;
+    value: T;
+    options: { value: T; label: string }[];
+    onChange: (value: T) => void;
+}) => (
+    <label className="flex items-center justify-between text-sm font-medium text-gray-700 dark:text-gray-300">
+        <span>{label}</span>
+        <select
+            value={value}
+            onChange={(e) => onChange(e.target.value as T)}
+            className={`${inputClass} w-48`}
+        >
+            {options.map((option) => (
+                <option key={option.value} value={option.value}>{option.label}</option>
+            ))}
+        </select>
+    </label>
+);
+
+const ProcessingSettings: React.FC<ProcessingSettingsProps> = ({ settings, onUpdateSettings }) => {
+    const sponsorblock = settings.sponsorblock ?? defaultSponsorBlock;
+    const loudness = settings.loudness ?? defaultLoudness;
+    const tagging = settings.tagging ?? defaultTagging;
+    const coverArt = settings.cover_art ?? defaultCoverArt;
+    const lyrics = settings.lyrics ?? defaultLyrics;
+    const playlist = settings.playlist ?? defaultPlaylist;
+
+    const update = (changes: Partial<SettingsType>) => onUpdateSettings({ ...settings, ...changes });
+
+    const toggleCategory = (category: SponsorCategory) => {
+        const categories = sponsorblock.categories.includes(category)
+            ? sponsorblock.categories.filter((c) => c !== category)
+            : [...sponsorblock.categories, category];
+        update({ sponsorblock: { ...sponsorblock, categories } });
+    };
+
+    return (
+        <>
+            <Section icon={Scissors} title="SponsorBlock">
+                <Select
+                    label="Segments"
+                    value={sponsorblock.mode}
+                    options={[
+                        { value: 'off', label: 'Keep' },
+                        { value: 'remove', label: 'Cut out' },
+                        { value: 'mark', label: 'Mark as chapters' },
+                    ]}
+                    onChange={(mode) => update({ sponsorblock: { ...sponsorblock, mode } })}
+                />
+                {sponsorblock.mode !== 'off' && (
+                    <>
+                        <div className="grid grid-cols-2 gap-2">
+                            {sponsorCategories.map(({ value, label }) => (
+                                <label key={value} className="flex items-center space-x-2 text-sm text-gray-700 dark:text-gray-300">
+                                    <input
+                                        type="checkbox"
+                                        checked={sponsorblock.categories.includes(value)}
+                                        onChange={() => toggleCategory(value)}
+                                    />
+                                    <span>{label}</span>
+                                </label>
+                            ))}
+                        </div>
+                        <Field
+                            label="API server"
+                            value={sponsorblock.api_url}
+                            onCommit={(api_url) => update({ sponsorblock: { ...sponsorblock, api_url } })}
+                        />
+                    </>
+                )}
+            </Section>
+
+            <Section icon={Volume2} title="Loudness">
+                <Select
+                    label="Mode"
+                    value={loudness.mode}
+                    options={[
+                        { value: 'off', label: 'Off' },
+                        { value: 'normalize', label: 'Normalize' },
+                        { value: 'replaygain', label: 'ReplayGain tags' },
+                    ]}
+                    onChange={(mode) => update({ loudness: { ...loudness, mode } })}
+                />
+                {loudness.mode === 'replaygain' && (
+                    <p className="text-sm text-gray-600 dark:text-gray-400">
+                        Every file gets track gain. Album gain is only written to tracks split from one video by chapters,
+                        not across a playlist.
+                    </p>
+                )}
+                {loudness.mode === 'normalize' && (
+                    <>
+                        <Field
+                            label="Target (LUFS)"
+                            type="number"
+                            value={String(loudness.target_lufs)}
+                            onCommit={(value) => update({ loudness: { ...loudness, target_lufs: Number(value) } })}
+                        />
+                        <Field
+                            label="True peak (dBTP)"
+                            type="number"
+                            value={String(loudness.true_peak)}
+                            onCommit={(value) => update({ loudness: { ...loudness, true_peak: Number(value) } })}
+                        />
+                    </>
+                )}
+            </Section>
+
+            <Section icon={Tag} title="Tags & Cover Art">
+                <Toggle
+                    label="Write tags"
+                    description="Artist, title, album and track number from the video and playlist"
+                    checked={tagging.enabled}
+                    onChange={(enabled) => update({ tagging: { ...tagging, enabled } })}
+                />
+                <Field
+                    label="Default genre"
+                    value={tagging.default_genre ?? ''}
+                    onCommit={(genre) => update({ tagging: { ...tagging, default_genre: genre.trim() || null } })}
+                />
+                <Toggle
+                    label="Square cover"
+                    description="Crop thumbnails to a square, like album art"
+                    checked={coverArt.square_crop}
+                    onChange={(square_crop) => update({ cover_art: { ...coverArt, square_crop } })}
+                />
+                <Field
+                    label="Largest cover size (px)"
+                    type="number"
+                    value={coverArt.max_size ? String(coverArt.max_size) : ''}
+                    placeholder="Original"
+                    onCommit={(value) => update({ cover_art: { ...coverArt, max_size: value ? Number(value) : null } })}
+                />
+                <Field
+                    label="Cover JPEG quality"
+                    type="number"
+                    value={String(coverArt.jpeg_quality)}
+                    onCommit={(value) => update({ cover_art: { ...coverArt, jpeg_quality: Number(value) } })}
+                />
+                <p className="text-sm text-gray-600 dark:text-gray-400">
+                    Cropping, size and quality apply to covers embedded in MP3, M4A and FLAC, and to cover.jpg.
+                    Opus, Vorbis and videos embed the original thumbnail.
+                </p>
+                <Toggle
+                    label="Save cover.jpg"
+                    description="Also keep the cover in album and chapter folders"
+                    checked={coverArt.save_cover_file}
+                    onChange={(save_cover_file) => update({ cover_art: { ...coverArt, save_cover_file } })}
+                />
+            </Section>
+
+            <Section icon={FileText} title="Lyrics">
+                <Toggle
+                    label="Synced lyrics (.lrc)"
+                    checked={lyrics.export_lrc}
+                    onChange={(export_lrc) => update({ lyrics: { ...lyrics, export_lrc } })}
+                />
+                <Toggle
+                    label="Plain lyrics (.txt)"
+                    checked={lyrics.export_txt}
+                    onChange={(export_txt) => update({ lyrics: { ...lyrics, export_txt } })}
+                />
+                <Toggle
+                    label="Embed lyrics"
+                    description="MP3 only"
+                    checked={lyrics.embed}
+                    onChange={(embed) => update({ lyrics: { ...lyrics, embed } })}
+                />
+                <Field
+                    label="Languages"
+                    value={lyrics.languages.join(', ')}
+                    placeholder="en, de"
+                    onCommit={(value) => update({
+                        lyrics: {
+                            ...lyrics,
+                            languages: value.split(',').map((l) => l.trim()).filter(Boolean),
+                        },
+                    })}
+                />
+                <Toggle
+                    label="Use automatic captions"
+                    description="When the video has no subtitles of its own"
+                    checked={lyrics.auto_captions}
+                    onChange={(auto_captions) => update({ lyrics: { ...lyrics, auto_captions } })}
+                />
+            </Section>
+
+            <Section icon={ListVideo} title="Playlists">
+                <Field
+                    label="Items to list from a mix"
+                    type="number"
+                    value={String(playlist.mix_item_cap)}
+                    onCommit={(value) => update({ playlist: { ...playlist, mix_item_cap: Number(value) } })}
+                />
+                <Field
+                    label="Fetch timeout (seconds)"
+                    type="number"
+                    value={String(playlist.fetch_timeout_secs)}
+                    onCommit={(value) => update({ playlist: { ...playlist, fetch_timeout_secs: Number(value) } })}
+                />
+            </Section>
+        </>
+    );
+};
+
+export default ProcessingSettings;
@@ -20,6 +20,10 @@ const ProgressBar: React.FC<ProgressBarProps> = ({ item }) => {
                 return `Error: ${item.error || 'Unknown error'}`;
             case 'cancelled':
                 return 'Download cancelled';
+            case 'retrying':
+                return item.retry
+                    ? `Retry ${item.retry.attempt} in ${item.retry.next_in}s...`
+                    : 'Retrying...';
             default:
                 return 'Unknown status';
         }
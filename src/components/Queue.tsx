@@ -1,11 +1,15 @@
 import React, { useState } from 'react';
-import { Trash2, Play, Square, CheckCircle, XCircle, Clock, AlertCircle, FolderOpen, Download } from 'lucide-react';
-import { QueueItem } from '../types';
+import { Trash2, Play, Square, CheckCircle, XCircle, Clock, AlertCircle, FolderOpen, Download, Tag } from 'lucide-react';
+import { ItemOutput, QueueItem, TagOverrides } from '../types';
 import ProgressBar from './ProgressBar';
+import TagEditor from './TagEditor';
+import OutputPicker from './OutputPicker';
 
 interface QueueProps {
     items: QueueItem[];
     onRemoveItem: (id: string) => void;
+    onUpdateTags: (id: string, tags: TagOverrides | undefined) => void;
+    onUpdateOutput: (id: string, output: ItemOutput) => void;
     onRetryItem: (id: string) => void;
     onClearCompleted: () => void;
     onClearPending: () => void;
@@ -21,6 +25,8 @@ interface QueueProps {
 const Queue: React.FC<QueueProps> = ({
     items,
     onRemoveItem,
+    onUpdateTags,
+    onUpdateOutput,
     onClearCompleted,
     onClearPending,
     onStopQueue,
@@ -53,7 +59,7 @@ const Queue: React.FC<QueueProps> = ({
         if (selectedItems.size === selectableItems.length) {
             setSelectedItems(new Set());
         } else {
-            setSelectedItems(new Set(selectableItems.map(item => item.id)));
+            setSelectedItems(new Set(selectableItems.map(item => item.job_id)));
         }
     };
 
@@ -190,16 +196,18 @@ const Queue: React.FC<QueueProps> = ({
                     <div className="space-y-3">
                         {pendingItems.map((item) => (
                             <QueueItemCard
-                                key={`pending-${item.id}`}
+                                key={`pending-${item.job_id}`}
                                 item={item}
                                 onRemoveItem={onRemoveItem}
+                                onUpdateTags={onUpdateTags}
+                                onUpdateOutput={onUpdateOutput}
                                 onOpenFile={onOpenFile}
                                 onOpenFolder={onOpenFolder}
                                 canShowActions={canShowActions}
                                 getStatusIcon={getStatusIcon}
                                 isSelectable={['pending', 'error', 'cancelled'].includes(item.status)}
-                                isSelected={selectedItems.has(item.id)}
-                                onSelect={() => handleItemSelect(item.id)}
+                                isSelected={selectedItems.has(item.job_id)}
+                                onSelect={() => handleItemSelect(item.job_id)}
                             />
                         ))}
                     </div>
@@ -227,9 +235,11 @@ const Queue: React.FC<QueueProps> = ({
                     <div className="space-y-3">
                         {completedItems.map((item) => (
                             <QueueItemCard
-                                key={`completed-${item.id}`}
+                                key={`completed-${item.job_id}`}
                                 item={item}
                                 onRemoveItem={onRemoveItem}
+                                onUpdateTags={onUpdateTags}
+                                onUpdateOutput={onUpdateOutput}
                                 onOpenFile={onOpenFile}
                                 onOpenFolder={onOpenFolder}
                                 canShowActions={canShowActions}
@@ -249,6 +259,8 @@ const Queue: React.FC<QueueProps> = ({
 interface QueueItemCardProps {
     item: QueueItem;
     onRemoveItem: (id: string) => void;
+    onUpdateTags: (id: string, tags: TagOverrides | undefined) => void;
+    onUpdateOutput: (id: string, output: ItemOutput) => void;
     onOpenFile: (path: string) => void;
     onOpenFolder: (path: string) => void;
     canShowActions: boolean;
@@ -261,6 +273,8 @@ interface QueueItemCardProps {
 const QueueItemCard: React.FC<QueueItemCardProps> = ({
     item,
     onRemoveItem,
+    onUpdateTags,
+    onUpdateOutput,
     onOpenFile,
     onOpenFolder,
     canShowActions,
@@ -269,6 +283,10 @@ const QueueItemCard: React.FC<QueueItemCardProps> = ({
     isSelected,
     onSelect
 }) => {
+    const [editingTags, setEditingTags] = useState(false);
+    // Tags and output choices only apply to items not downloaded yet
+    const canEdit = canShowActions && ['pending', 'error', 'cancelled'].includes(item.status);
+
     const handleCardClick = (e: React.MouseEvent) => {
         // Only handle selection if the item is selectable and the click wasn't on an action button
         if (isSelectable && !(e.target as HTMLElement).closest('button')) {
@@ -316,6 +334,19 @@ const QueueItemCard: React.FC<QueueItemCardProps> = ({
                         </h3>
                     </div>
                     <ProgressBar item={item} />
+                    {canEdit && (
+                        <OutputPicker
+                            item={item}
+                            onChange={(output) => onUpdateOutput(item.job_id, output)}
+                        />
+                    )}
+                    {editingTags && (
+                        <TagEditor
+                            item={item}
+                            onSave={(tags) => onUpdateTags(item.job_id, tags)}
+                            onClose={() => setEditingTags(false)}
+                        />
+                    )}
                 </div>
 
                 <div className="flex items-center space-x-2">
@@ -345,11 +376,24 @@ const QueueItemCard: React.FC<QueueItemCardProps> = ({
                         </>
                     )}
 
+                    {canEdit && (
+                        <button
+                            onClick={(e) => {
+                                e.stopPropagation();
+                                setEditingTags(!editingTags);
+                            }}
+                            className={`p-2 transition-colors ${item.tags ? 'text-blue-500' : 'text-gray-400'} hover:text-blue-600`}
+                            title="Edit tags"
+                        >
+                            <Tag size={16} />
+                        </button>
+                    )}
+
                     {(item.status === 'completed' || canShowActions) && (
                         <button
                             onClick={(e) => {
                                 e.stopPropagation();
-                                onRemoveItem(item.id);
+                                onRemoveItem(item.job_id);
                             }}
                             className="p-2 text-gray-400 hover:text-red-600 transition-colors"
                             title="Remove from queue"
@@ -0,0 +1,86 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import toast from 'react-hot-toast';
+import { RotateCcw, X } from 'lucide-react';
+import { useQueueStore } from '../store/queueStore';
+import { QueuedJob } from '../types';
+import { describeError } from '../utils/errors';
+
+// Offers to resume downloads left unfinished when the app was last closed
+const ResumePrompt = () => {
+    const [jobs, setJobs] = useState<QueuedJob[]>([]);
+
+    useEffect(() => {
+        invoke<QueuedJob[]>('get_unfinished_downloads')
+            .then(setJobs)
+            .catch((error) => console.error('Failed to load unfinished downloads:', error));
+    }, []);
+
+    if (jobs.length === 0) {
+        return null;
+    }
+
+    const ids = jobs.map((job) => job.item.job_id ?? job.item.id);
+
+    const handleResume = async () => {
+        const { addItems, updateItemStatus, setProcessing } = useQueueStore.getState();
+
+        // Show the jobs in the queue, even if the saved frontend queue was lost
+        addItems(jobs.map((job) => ({
+            id: job.item.id,
+            job_id: job.item.job_id ?? job.item.id,
+            url: job.item.url,
+            title: job.item.title,
+            duration: job.item.duration ?? undefined,
+        })));
+        ids.forEach((id) => updateItemStatus(id, 'pending'));
+
+        setJobs([]);
+        setProcessing(true);
+        toast.success(`Resuming ${ids.length} download(s)`);
+
+        try {
+            await invoke('resume_unfinished_downloads', { ids });
+        } catch (error) {
+            console.error('Failed to resume downloads:', error);
+            toast.error(describeError(error, 'Failed to resume downloads'));
+            setProcessing(false);
+        }
+    };
+
+    const handleDiscard = async () => {
+        setJobs([]);
+
+        try {
+            await invoke('discard_unfinished_downloads', { ids });
+        } catch (error) {
+            console.error('Failed to discard downloads:', error);
+        }
+    };
+
+    return (
+        <div className="mb-6 flex items-center justify-between p-4 bg-blue-50 dark:bg-blue-900/20 border border-blue-200 dark:border-blue-800 rounded-lg">
+            <span className="text-gray-900 dark:text-white">
+                {jobs.length} download(s) were left unfinished last time.
+            </span>
+            <div className="flex items-center space-x-2">
+                <button
+                    onClick={handleResume}
+                    className="flex items-center space-x-2 px-3 py-2 bg-blue-600 hover:bg-blue-700 text-white rounded-lg transition-colors"
+                >
+                    <RotateCcw size={16} />
+                    <span>Resume</span>
+                </button>
+                <button
+                    onClick={handleDiscard}
+                    className="flex items-center space-x-2 px-3 py-2 text-red-600 hover:text-red-700 hover:bg-red-50 dark:hover:bg-red-900/20 rounded-lg transition-colors"
+                >
+                    <X size={16} />
+                    <span>Discard</span>
+                </button>
+            </div>
+        </div>
+    );
+};
+
+export default ResumePrompt;
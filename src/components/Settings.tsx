@@ -2,6 +2,8 @@ import React from 'react';
 import { Folder, Save, Sun, Moon, Monitor } from 'lucide-react';
 import { Settings as SettingsType } from '../types';
 import { useTheme } from '../contexts/ThemeContext';
+import NamingSettings from './NamingSettings';
+import ProcessingSettings from './ProcessingSettings';
 
 interface SettingsProps {
     settings: SettingsType;
@@ -90,6 +92,9 @@ const Settings: React.FC<SettingsProps> = ({
                 </div>
             </div>
 
+            {/* File Naming */}
+            <NamingSettings settings={settings} onUpdateSettings={onUpdateSettings} />
+
             {/* Download Settings */}
             <div className="bg-white dark:bg-gray-800 rounded-lg p-6 border border-gray-200 dark:border-gray-700 transition-colors">
                 <div className="flex items-center space-x-3 mb-4">
@@ -131,6 +136,9 @@ const Settings: React.FC<SettingsProps> = ({
                 </div>
             </div>
 
+            {/* Post-processing */}
+            <ProcessingSettings settings={settings} onUpdateSettings={onUpdateSettings} />
+
             {/* About */}
             <div className="bg-white dark:bg-gray-800 rounded-lg p-6 border border-gray-200 dark:border-gray-700 transition-colors">
                 <h3 className="text-lg font-medium text-gray-900 dark:text-white mb-4">
@@ -0,0 +1,97 @@
+import React, { useState } from 'react';
+import { QueueItem, TagOverrides } from '../types';
+
+interface TagEditorProps {
+    item: QueueItem;
+    onSave: (tags: TagOverrides | undefined) => void;
+    onClose: () => void;
+}
+
+const textFields = [
+    { key: 'title', label: 'Title' },
+    { key: 'artist', label: 'Artist' },
+    { key: 'album', label: 'Album' },
+    { key: 'year', label: 'Year' },
+    { key: 'genre', label: 'Genre' },
+] as const;
+
+// Tags typed here replace the ones derived from the video and playlist
+const TagEditor: React.FC<TagEditorProps> = ({ item, onSave, onClose }) => {
+    const [tags, setTags] = useState<TagOverrides>(item.tags ?? {});
+
+    const handleSave = () => {
+        // Blank fields fall back to the derived tags
+        const cleaned: TagOverrides = {};
+        for (const { key } of textFields) {
+            const value = tags[key]?.trim();
+            if (value) {
+                cleaned[key] = value;
+            }
+        }
+        if (tags.track_number && tags.track_number > 0) {
+            cleaned.track_number = tags.track_number;
+        }
+
+        onSave(Object.keys(cleaned).length > 0 ? cleaned : undefined);
+        onClose();
+    };
+
+    // Mixes and channels do not give the album a name
+    const albumPlaceholder = !item.playlist_kind || item.playlist_kind === 'playlist' ? item.playlist_title : undefined;
+
+    const inputClass = 'w-full px-2 py-1 bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 rounded text-sm text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-blue-500';
+
+    return (
+        // Clicks in the editor must not toggle the card's selection
+        <div
+            className="mt-3 p-3 bg-gray-50 dark:bg-gray-700/50 rounded border border-gray-200 dark:border-gray-600"
+            onClick={(e) => e.stopPropagation()}
+        >
+            <div className="grid grid-cols-2 gap-2">
+                {textFields.map(({ key, label }) => (
+                    <label key={key} className="text-xs text-gray-600 dark:text-gray-400">
+                        {label}
+                        <input
+                            type="text"
+                            value={tags[key] ?? ''}
+                            placeholder={key === 'title' ? item.title : key === 'album' ? albumPlaceholder : undefined}
+                            onChange={(e) => setTags({ ...tags, [key]: e.target.value })}
+                            className={inputClass}
+                        />
+                    </label>
+                ))}
+                <label className="text-xs text-gray-600 dark:text-gray-400">
+                    Track number
+                    <input
+                        type="number"
+                        min={1}
+                        value={tags.track_number ?? ''}
+                        placeholder={item.playlist_index?.toString()}
+                        onChange={(e) => setTags({
+                            ...tags,
+                            track_number: e.target.value ? Number(e.target.value) : undefined,
+                        })}
+                        className={inputClass}
+                    />
+                </label>
+            </div>
+
+            <div className="flex justify-end space-x-2 mt-3">
+                <button
+                    onClick={onClose}
+                    className="px-3 py-1 text-sm text-gray-600 dark:text-gray-300 hover:text-gray-900 dark:hover:text-white transition-colors"
+                >
+                    Cancel
+                </button>
+                <button
+                    onClick={handleSave}
+                    className="px-3 py-1 text-sm bg-blue-600 hover:bg-blue-700 text-white rounded font-medium transition-colors"
+                >
+                    Save Tags
+                </button>
+            </div>
+        </div>
+    );
+};
+
+export default TagEditor;
@@ -3,11 +3,14 @@ import toast from 'react-hot-toast';
 import Queue from '../components/Queue';
 import { useQueueStore } from '../store/queueStore';
 import { useProgress } from '../hooks/useProgress';
+import { describeError } from '../utils/errors';
 
 const Downloads = () => {
     const {
         items,
         removeItem,
+        updateItemTags,
+        updateItemOutput,
         clearCompleted,
         startQueue,
         stopQueue,
@@ -23,15 +26,15 @@ const Downloads = () => {
         const itemsToRemove = items.filter(item =>
             ['pending', 'error', 'cancelled'].includes(item.status)
         );
-        itemsToRemove.forEach(item => removeItem(item.id));
+        itemsToRemove.forEach(item => removeItem(item.job_id));
     };
 
     const handleStartSelected = (selectedIds: string[]) => {
         // Reset selected items to pending status before starting
-        selectedIds.forEach(id => {
-            const item = items.find(item => item.id === id);
+        selectedIds.forEach(jobId => {
+            const item = items.find(item => item.job_id === jobId);
             if (item && ['error', 'cancelled'].includes(item.status)) {
-                updateItemStatus(id, 'pending' as any);
+                updateItemStatus(jobId, 'pending' as any);
             }
         });
 
@@ -44,7 +47,7 @@ const Downloads = () => {
             await invoke('open_file', { path });
         } catch (error) {
             console.error('Failed to open file:', error);
-            toast.error('Failed to open file: ' + (error as string));
+            toast.error(describeError(error, 'Failed to open file'));
         }
     };
 
@@ -53,7 +56,7 @@ const Downloads = () => {
             await invoke('open_folder', { path });
         } catch (error) {
             console.error('Failed to open folder:', error);
-            toast.error('Failed to open folder: ' + (error as string));
+            toast.error(describeError(error, 'Failed to open folder'));
         }
     };
 
@@ -71,6 +74,8 @@ const Downloads = () => {
             <Queue
                 items={items}
                 onRemoveItem={removeItem}
+                onUpdateTags={updateItemTags}
+                onUpdateOutput={updateItemOutput}
                 onRetryItem={() => { }} // No longer used
                 onClearCompleted={clearCompleted}
                 onClearPending={handleClearPending}
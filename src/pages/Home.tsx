@@ -1,17 +1,21 @@
-import { useState } from 'react';
+import { useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import toast from 'react-hot-toast';
 import UrlInput from '../components/UrlInput';
 import ItemList from '../components/ItemList';
 import { useQueueStore } from '../store/queueStore';
 import { usePlaylistStore } from '../store/playlistStore';
-import { Item } from '../types';
+import { ErrorInfo, Item, PlaylistInfo, PlaylistItemEvent } from '../types';
 
 const Home = () => {
     const {
         items,
+        playlist,
         loading,
         setItems,
+        addItem,
+        setPlaylist,
         setLoading,
         setCurrentUrl,
         clearPlaylist
@@ -19,33 +23,76 @@ const Home = () => {
 
     const { addItems } = useQueueStore();
     const [url, setUrl] = useState('');
+    const fetchId = useRef<string | null>(null);
 
     const handleUrlSubmit = async (submittedUrl: string) => {
         setItems([]); // Clear the list before fetching new items
+        setPlaylist(null);
         setLoading(true);
         setCurrentUrl(submittedUrl);
 
+        const requestId = crypto.randomUUID();
+        fetchId.current = requestId;
+
+        // Show items as yt-dlp lists them instead of waiting for the whole playlist
+        const unlisten = await listen<PlaylistItemEvent>('playlist-item', (event) => {
+            if (event.payload.request_id === requestId) {
+                addItem(event.payload.item);
+            }
+        });
+
         try {
-            const fetchedItems = await invoke<Item[]>('fetch_playlist', { url: submittedUrl });
+            const info = await invoke<PlaylistInfo>('fetch_playlist', {
+                requestId,
+                url: submittedUrl,
+            });
+            const fetchedItems = info.items;
             setItems(fetchedItems);
+            setPlaylist(info);
 
             if (fetchedItems.length === 0) {
                 toast.error('No items found in the playlist');
             } else if (fetchedItems.length === 1) {
                 toast.success('Video loaded successfully');
+            } else if (info.truncated) {
+                const total = info.item_count ? ` of ${info.item_count}` : '';
+                toast.success(`First ${fetchedItems.length}${total} items loaded from playlist`);
             } else {
                 toast.success(`${fetchedItems.length} items loaded from playlist`);
             }
         } catch (error) {
+            // Items streamed before the failure look like a complete list, so drop them
+            setItems([]);
+
+            const info = error as Partial<ErrorInfo>;
+            if (info.code === 'fetch_cancelled') {
+                toast('Fetch cancelled');
+                return;
+            }
+
             console.error('Error fetching playlist:', error);
-            toast.error('Failed to fetch playlist. Please check the URL and try again.');
+            toast.error(info.message
+                ? [info.message, info.hint].filter(Boolean).join('. ')
+                : 'Failed to fetch playlist. Please check the URL and try again.');
         } finally {
+            unlisten();
+            fetchId.current = null;
             setLoading(false);
         }
     };
 
+    const handleCancelFetch = async () => {
+        if (!fetchId.current) return;
+
+        try {
+            await invoke('cancel_fetch', { requestId: fetchId.current });
+        } catch (error) {
+            console.error('Error cancelling fetch:', error);
+        }
+    };
+
     const handleAddToQueue = (selectedItems: Item[]) => {
-        addItems(selectedItems);
+        addItems(selectedItems, playlist);
         toast.success(`${selectedItems.length} item(s) added to queue`);
     };
 
@@ -80,7 +127,38 @@ const Home = () => {
                 <div className="mt-8 text-center">
                     <div className="inline-flex items-center space-x-2 text-gray-600 dark:text-gray-400">
                         <div className="w-4 h-4 border-2 border-blue-600 border-t-transparent rounded-full animate-spin" />
-                        <span>Fetching playlist items...</span>
+                        <span>Fetching playlist items{items.length > 0 ? ` (${items.length} so far)` : ''}...</span>
+                        <button
+                            onClick={handleCancelFetch}
+                            className="text-sm text-red-600 dark:text-red-400 hover:underline"
+                        >
+                            Cancel
+                        </button>
+                    </div>
+                </div>
+            )}
+
+            {playlist && playlist.kind !== 'video' && (
+                <div className="mt-8 flex items-center space-x-4">
+                    {playlist.thumbnail && (
+                        <img
+                            src={playlist.thumbnail}
+                            alt=""
+                            className="w-24 h-14 object-cover rounded"
+                        />
+                    )}
+                    <div>
+                        <h2 className="text-xl font-semibold text-gray-900 dark:text-white">
+                            {playlist.title}
+                        </h2>
+                        <p className="text-sm text-gray-600 dark:text-gray-400">
+                            {[
+                                playlist.uploader,
+                                playlist.item_count != null
+                                    ? `${playlist.item_count} items`
+                                    : `${playlist.items.length}${playlist.truncated ? '+' : ''} items`,
+                            ].filter(Boolean).join(' • ')}
+                        </p>
                     </div>
                 </div>
             )}
@@ -3,6 +3,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 import toast from 'react-hot-toast';
 import Settings from '../components/Settings';
 import { useSettingsStore } from '../store/settingsStore';
+import { describeError } from '../utils/errors';
 
 const SettingsPage = () => {
     const { settings, updateSettings } = useSettingsStore();
@@ -23,7 +24,7 @@ const SettingsPage = () => {
             }
         } catch (error) {
             console.error('Error selecting folder:', error);
-            toast.error('Failed to select folder');
+            toast.error(describeError(error, 'Failed to select folder'));
         }
     };
 
@@ -36,7 +37,7 @@ const SettingsPage = () => {
             toast.success('Settings saved');
         } catch (error) {
             console.error('Error saving settings:', error);
-            toast.error('Failed to save settings');
+            toast.error(describeError(error, 'Failed to save settings'));
         }
     };
 
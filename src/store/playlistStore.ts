@@ -1,11 +1,15 @@
 import { create } from 'zustand';
-import { Item } from '../types';
+import { Item, PlaylistInfo } from '../types';
 
 interface PlaylistState {
     items: Item[];
+    // Set once a fetch completes
+    playlist: PlaylistInfo | null;
     loading: boolean;
     currentUrl: string;
     setItems: (items: Item[]) => void;
+    addItem: (item: Item) => void;
+    setPlaylist: (playlist: PlaylistInfo | null) => void;
     setLoading: (loading: boolean) => void;
     setCurrentUrl: (url: string) => void;
     clearPlaylist: () => void;
@@ -13,10 +17,13 @@ interface PlaylistState {
 
 export const usePlaylistStore = create<PlaylistState>((set) => ({
     items: [],
+    playlist: null,
     loading: false,
     currentUrl: '',
     setItems: (items) => set({ items }),
+    addItem: (item) => set((state) => ({ items: [...state.items, item] })),
+    setPlaylist: (playlist) => set({ playlist }),
     setLoading: (loading) => set({ loading }),
     setCurrentUrl: (url) => set({ currentUrl: url }),
-    clearPlaylist: () => set({ items: [], currentUrl: '' }),
+    clearPlaylist: () => set({ items: [], playlist: null, currentUrl: '' }),
 }));
\ No newline at end of file
@@ -1,28 +1,35 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
-import { Item, QueueItem, ProgressEvent, DownloadStatus } from '../types';
+import { Item, ItemOutput, PlaylistInfo, QueueItem, ProgressEvent, DownloadStatus, TagOverrides } from '../types';
 import { useSettingsStore } from './settingsStore';
 import toast from 'react-hot-toast';
+import { describeError } from '../utils/errors';
 
 interface QueueStore {
     items: QueueItem[];
     isProcessing: boolean;
     isStopping: boolean;
     saveTimeout?: NodeJS.Timeout;
-    addItems: (items: Item[]) => void;
-    removeItem: (id: string) => void;
-    retryItem: (id: string) => void;
+    addItems: (items: (Item & { job_id?: string })[], playlist?: PlaylistInfo | null) => void;
+    removeItem: (jobId: string) => void;
+    retryItem: (jobId: string) => void;
+    updateItemTags: (jobId: string, tags: TagOverrides | undefined) => void;
+    updateItemOutput: (jobId: string, output: ItemOutput) => void;
     clearCompleted: () => void;
     updateItemProgress: (event: ProgressEvent) => void;
-    startQueue: (selectedIds: string[]) => Promise<void>;
+    startQueue: (selectedJobIds: string[]) => Promise<void>;
     stopQueue: () => Promise<void>;
     setProcessing: (processing: boolean) => void;
     setStopping: (stopping: boolean) => void;
     loadPersistedQueue: () => void;
     saveQueue: () => void;
-    updateItemStatus: (id: string, status: DownloadStatus) => void
+    updateItemStatus: (jobId: string, status: DownloadStatus) => void
 }
 
+// Entries still on the settings' output may not be queued twice for one video
+const usesDefaultOutput = (item: QueueItem) =>
+    !item.audio_format && !item.preset && !item.mode && !item.video && !item.split_chapters;
+
 const STORAGE_KEY = 'youtube-to-mp3-queue';
 
 export const useQueueStore = create<QueueStore>((set, get) => ({
@@ -39,6 +46,8 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
                     // Reset any processing states on app restart
                     const items = data.items.map((item: QueueItem) => ({
                         ...item,
+                        // Saved before entries had their own id
+                        job_id: item.job_id ?? item.id,
                         status: ['downloading', 'processing', 'pending'].includes(item.status) ? 'pending' : item.status,
                         progress: ['downloading', 'processing', 'pending'].includes(item.status) ? 0 : item.progress,
                         eta: undefined,
@@ -65,12 +74,23 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
         }
     },
 
-    addItems: (items: Item[]) => {
+    addItems: (items: (Item & { job_id?: string })[], playlist?: PlaylistInfo | null) => {
         const currentItems = get().items;
+        // Mixes and channels still name {playlist} folders; the backend only
+        // uses a real playlist as the album
+        const source = playlist && playlist.kind !== 'video' ? playlist : null;
         const newItems = items
-            .filter(item => !currentItems.some(existing => existing.id === item.id))
+            // A video can be queued again once its entry has output choices of its own
+            .filter(item => !currentItems.some(existing =>
+                existing.job_id === item.job_id
+                || (existing.id === item.id && usesDefaultOutput(existing))
+            ))
             .map(item => ({
                 ...item,
+                job_id: item.job_id ?? crypto.randomUUID(),
+                playlist_title: source?.title,
+                playlist_kind: source?.kind,
+                playlist_count: source?.item_count ?? undefined,
                 status: 'pending' as const,
                 progress: 0,
                 addedAt: Date.now(),
@@ -84,17 +104,17 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
         setTimeout(() => get().saveQueue(), 0);
     },
 
-    removeItem: (id: string) => {
+    removeItem: (jobId: string) => {
         set(state => ({
-            items: state.items.filter(item => item.id !== id)
+            items: state.items.filter(item => item.job_id !== jobId)
         }));
         setTimeout(() => get().saveQueue(), 0);
     },
 
-    retryItem: (id: string) => {
+    retryItem: (jobId: string) => {
         set(state => ({
             items: state.items.map(item =>
-                item.id === id
+                item.job_id === jobId
                     ? { ...item, status: 'pending', progress: 0, error: undefined, eta: undefined, speed: undefined }
                     : item
             )
@@ -102,6 +122,20 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
         setTimeout(() => get().saveQueue(), 0);
     },
 
+    updateItemTags: (jobId: string, tags: TagOverrides | undefined) => {
+        set(state => ({
+            items: state.items.map(item => item.job_id === jobId ? { ...item, tags } : item)
+        }));
+        setTimeout(() => get().saveQueue(), 0);
+    },
+
+    updateItemOutput: (jobId: string, output: ItemOutput) => {
+        set(state => ({
+            items: state.items.map(item => item.job_id === jobId ? { ...item, ...output } : item)
+        }));
+        setTimeout(() => get().saveQueue(), 0);
+    },
+
     clearCompleted: () => {
         set(state => ({
             items: state.items.filter(item => item.status !== 'completed')
@@ -112,23 +146,27 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
     updateItemProgress: (event: ProgressEvent) => {
         set(state => {
             const { settings } = useSettingsStore.getState();
+            const status: DownloadStatus = typeof event.status === 'string' ? event.status : 'retrying';
 
             const updatedItems = state.items.map(item => {
-                if (item.id === event.id) {
+                if (item.job_id === event.id) {
                     let downloadPath = item.downloadPath;
 
-                    // Generate download path when status becomes completed
-                    if (event.status === 'completed' && !downloadPath) {
+                    // Prefer the path the backend reports; fall back to guessing from the title
+                    if (status === 'completed' && event.output_path) {
+                        downloadPath = event.output_path;
+                    } else if (status === 'completed' && !downloadPath) {
                         const cleanTitle = item.title
                             .replace(/[<>:"/\\|?*]/g, '')
                             .replace(/\s+/g, ' ')
                             .trim();
-                        downloadPath = `${settings.download_folder}/${cleanTitle}.mp3`;
+                        downloadPath = `${settings.download_folder}/${cleanTitle}.${item.audio_format ?? settings.audio_format}`;
                     }
 
                     return {
                         ...item,
-                        status: event.status,
+                        status,
+                        retry: typeof event.status === 'string' ? undefined : event.status.retrying,
                         progress: event.progress,
                         eta: event.eta,
                         speed: event.speed,
@@ -140,7 +178,7 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
             });
 
             // Immediate save for critical status changes
-            if (['completed', 'error', 'cancelled'].includes(event.status)) {
+            if (['completed', 'error', 'cancelled'].includes(status)) {
                 setTimeout(() => {
                     try {
                         const data = {
@@ -178,9 +216,9 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
         set({ isStopping: stopping });
     },
 
-    startQueue: async (selectedIds: string[]) => {
+    startQueue: async (selectedJobIds: string[]) => {
         const { items, setProcessing } = get();
-        const pendingItems = items.filter(item => selectedIds.includes(item.id));
+        const pendingItems = items.filter(item => selectedJobIds.includes(item.job_id));
 
         if (pendingItems.length === 0) {
             toast.error('No pending items to download');
@@ -196,19 +234,31 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
             await invoke('start_downloads', {
                 items: pendingItems.map(item => ({
                     id: item.id,
+                    job_id: item.job_id,
                     url: item.url,
                     title: item.title,
+                    duration: item.duration,
+                    split_chapters: item.split_chapters ?? false,
+                    chapters: item.chapters,
+                    start_time: item.start_time,
+                    end_time: item.end_time,
+                    tags: item.tags,
+                    playlist_title: item.playlist_title,
+                    playlist_index: item.playlist_title ? item.playlist_index : undefined,
+                    playlist_kind: item.playlist_kind,
+                    playlist_count: item.playlist_count,
+                    audio_format: item.audio_format,
+                    preset: item.preset,
+                    mode: item.mode,
+                    video: item.video,
                 })),
-                settings: {
-                    download_folder: settings.download_folder,
-                    parallel_downloads: settings.parallel_downloads,
-                }
+                settings,
             });
 
             toast.success(`Started downloading ${pendingItems.length} item(s)`);
         } catch (error) {
             console.error('Error starting downloads:', error);
-            toast.error('Failed to start downloads');
+            toast.error(describeError(error, 'Failed to start downloads'));
             setProcessing(false);
         }
     },
@@ -222,14 +272,14 @@ export const useQueueStore = create<QueueStore>((set, get) => ({
             toast.success('Stopping downloads...');
         } catch (error) {
             console.error('Error stopping downloads:', error);
-            toast.error('Failed to stop downloads');
+            toast.error(describeError(error, 'Failed to stop downloads'));
             setStopping(false);
         }
     },
-    updateItemStatus: (id: string, status: DownloadStatus) => {
+    updateItemStatus: (jobId: string, status: DownloadStatus) => {
         set((state) => ({
             items: state.items.map(item =>
-                item.id === id ? { ...item, status: status as DownloadStatus } : item
+                item.job_id === jobId ? { ...item, status: status as DownloadStatus } : item
             )
         }));
     }
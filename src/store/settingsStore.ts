@@ -12,6 +12,7 @@ interface SettingsStore {
 const defaultSettings: Settings = {
     download_folder: './downloads',
     parallel_downloads: 1,
+    audio_format: 'mp3',
 };
 
 export const useSettingsStore = create<SettingsStore>()(
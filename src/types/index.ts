@@ -4,30 +4,238 @@ export interface Item {
     duration?: string;
     thumbnail?: string;
     url: string;
+    uploader?: string;
+    // Unset for items listed from a playlist; empty when the video has none
+    chapters?: Chapter[] | null;
+    // 1-based position in the playlist it was listed from
+    playlist_index?: number | null;
 }
 
-export type DownloadStatus = 'pending' | 'selected' | 'downloading' | 'processing' | 'completed' | 'error' | 'cancelled';
+export type PlaylistKind = 'video' | 'playlist' | 'mix' | 'channel';
+
+export interface PlaylistInfo {
+    id: string;
+    title: string;
+    uploader?: string | null;
+    thumbnail?: string | null;
+    // Size of the whole playlist; unknown for mixes
+    item_count?: number | null;
+    kind: PlaylistKind;
+    items: Item[];
+    // More items follow the last one listed
+    truncated: boolean;
+}
+
+// Sent for each item while a playlist is being fetched
+export interface PlaylistItemEvent {
+    request_id: string;
+    index: number;
+    item: Item;
+}
+
+export interface PlaylistCompleteEvent {
+    request_id: string;
+    loaded: number;
+    item_count?: number | null;
+    truncated: boolean;
+}
+
+export interface Chapter {
+    title: string;
+    start_time: number;
+    end_time: number;
+}
+
+export type DownloadStatus = 'pending' | 'selected' | 'downloading' | 'processing' | 'completed' | 'error' | 'cancelled' | 'paused' | 'skipped' | 'retrying';
+
+// Status as sent by the backend; retries carry their attempt number and delay
+export type ProgressStatus = Exclude<DownloadStatus, 'retrying'>
+    | { retrying: { attempt: number; next_in: number } };
 
 export interface QueueItem extends Item {
+    // Identifies the queue entry, as the same video may be queued more than once
+    job_id: string;
     status: DownloadStatus;
     progress: number;
+    // Set while waiting to retry a failed attempt
+    retry?: { attempt: number; next_in: number };
     eta?: string;
     speed?: string;
     error?: string;
     addedAt: number;
     downloadPath?: string;
+    // Write one file per chapter
+    split_chapters?: boolean;
+    // Only download this part, e.g. "12:30" to "18:45"
+    start_time?: string;
+    end_time?: string;
+    // Tags to use instead of the ones derived from the video
+    tags?: TagOverrides;
+    // Overrides the format from settings for this item only
+    audio_format?: AudioFormat;
+    // Name of a quality preset from settings
+    preset?: string;
+    mode?: DownloadMode;
+    video?: VideoOptions;
+    // The playlist, mix or channel the item was picked from. Only a
+    // playlist's title is used as the album.
+    playlist_title?: string;
+    playlist_kind?: PlaylistKind;
+    playlist_count?: number;
+}
+
+// Per-item choices that override the settings
+export type ItemOutput = Pick<QueueItem, 'audio_format' | 'preset' | 'mode' | 'video' | 'split_chapters'>;
+
+export interface TagOverrides {
+    artist?: string;
+    title?: string;
+    album?: string;
+    track_number?: number;
+    year?: string;
+    genre?: string;
+}
+
+export interface ErrorInfo {
+    code: string;
+    message: string;
+    hint?: string | null;
+    detail?: string | null;
 }
 
 export interface ProgressEvent {
     id: string;
-    status: DownloadStatus;
+    status: ProgressStatus;
     progress: number;
     eta?: string;
     speed?: string;
+    downloaded?: string;
+    total_size?: string;
     error?: string;
+    error_info?: ErrorInfo | null;
+    speed_bps?: number;
+    eta_seconds?: number;
+    downloaded_bytes?: number;
+    total_bytes?: number;
+    partial_file?: string;
+    output_path?: string | null;
+    file_size?: number | null;
+    // Set when the file name was already taken
+    collision?: CollisionPolicy | null;
+    // Set once per track while splitting by chapters
+    track?: { number: number; total?: number | null; title: string } | null;
+    // Set on completion when SponsorBlock segments were cut out
+    removed_segments?: SponsorSegment[] | null;
+    // Which of the app's own post-processing steps is running
+    stage?: ProcessingStage | null;
+}
+
+export type ProcessingStage = 'normalizing' | 'tagging' | 'cover_art' | 'lyrics';
+
+export interface CoverArtOptions {
+    square_crop: boolean;
+    max_size?: number | null;
+    // 1 (smallest) to 100 (best)
+    jpeg_quality: number;
+    // Also keep cover.jpg in the album folder
+    save_cover_file: boolean;
+}
+
+export interface LyricsOptions {
+    // Synced .lrc and plain .txt files next to the audio
+    export_lrc: boolean;
+    export_txt: boolean;
+    // Unsynced lyrics inside the file (MP3 only)
+    embed: boolean;
+    // Subtitle languages in order of preference, e.g. ['en', 'de']
+    languages: string[];
+    auto_captions: boolean;
+}
+
+export interface LoudnessOptions {
+    mode: 'off' | 'normalize' | 'replaygain';
+    target_lufs: number;
+    true_peak: number;
+}
+
+export type SponsorCategory = 'sponsor' | 'intro' | 'outro' | 'selfpromo' | 'music_offtopic';
+
+export interface SponsorBlockOptions {
+    mode: 'off' | 'remove' | 'mark';
+    categories: SponsorCategory[];
+    api_url: string;
+}
+
+export interface SponsorSegment {
+    category: string;
+    start_time: number;
+    end_time: number;
+}
+
+export type CollisionPolicy = 'skip' | 'overwrite' | 'rename' | 'append_id';
+
+export type AudioFormat = 'mp3' | 'm4a' | 'opus' | 'flac' | 'wav' | 'vorbis';
+
+export type DownloadMode = 'audio' | 'video';
+
+export type VideoFormat = 'mp4' | 'webm' | 'mkv';
+
+export interface VideoOptions {
+    format: VideoFormat;
+    max_resolution?: number | null;
+}
+
+export type AudioQuality =
+    | { mode: 'cbr'; bitrate: number }
+    | { mode: 'vbr'; level: number };
+
+export interface QualityPreset {
+    name: string;
+    quality: AudioQuality;
+    mono: boolean;
 }
 
 export interface Settings {
     download_folder: string;
     parallel_downloads: number;
-}
\ No newline at end of file
+    mode?: DownloadMode;
+    audio_format: AudioFormat;
+    quality_presets?: QualityPreset[];
+    default_preset?: string | null;
+    video?: VideoOptions;
+    use_download_archive?: boolean;
+    retry?: { max_retries: number; initial_delay_secs: number };
+    // Tokens: {artist} {title} {uploader} {playlist} {index} {upload_date} {id}, e.g. {index:02}
+    filename_template?: string;
+    folder_template?: string;
+    collision_policy?: CollisionPolicy;
+    sponsorblock?: SponsorBlockOptions;
+    loudness?: LoudnessOptions;
+    tagging?: { enabled: boolean; default_genre?: string | null };
+    cover_art?: CoverArtOptions;
+    lyrics?: LyricsOptions;
+    // Mixes never end, so listing one stops after this many items
+    playlist?: { mix_item_cap: number; fetch_timeout_secs: number };
+}
+
+export interface QueuedJob {
+    item: { id: string; job_id?: string | null; url: string; title: string; duration?: string | null };
+    download_folder: string;
+    status: DownloadStatus;
+    last_progress?: ProgressEvent | null;
+    partial_file?: string | null;
+}
+
+export interface HistoryEntry {
+    id: number;
+    url: string;
+    video_id: string;
+    title?: string | null;
+    output_path?: string | null;
+    format: string;
+    file_size?: number | null;
+    duration?: string | null;
+    finished_at: number;
+    success: boolean;
+    error?: string | null;
+}
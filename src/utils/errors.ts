@@ -0,0 +1,12 @@
+import { ErrorInfo } from '../types';
+
+// Turn an error rejected by a backend command into toast text
+export const describeError = (error: unknown, fallback: string): string => {
+    const info = error as Partial<ErrorInfo> | null;
+    const message = info?.detail ?? info?.message;
+    if (!message) {
+        return typeof error === 'string' ? error : fallback;
+    }
+
+    return [message, info?.hint].filter(Boolean).join('. ');
+};